@@ -0,0 +1,25 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use stellar_badge_wasm::stellar::stellar_data::TOMLCurrency;
+use stellar_badge_wasm::util::proof_encoding::Proof;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(encoded) = std::str::from_utf8(data) {
+        let catalog = vec![
+            TOMLCurrency {
+                code: String::from("SQ0101"),
+                ..TOMLCurrency::default()
+            },
+            TOMLCurrency {
+                code: String::from("SSQ01"),
+                ..TOMLCurrency::default()
+            },
+        ];
+        if let Ok(proof) = Proof::decode_v1(&String::from(encoded), &catalog) {
+            let _ = proof.encode_v1();
+        }
+        if let Ok(proof) = Proof::decode(&String::from(encoded), &catalog) {
+            let _ = proof.encode();
+        }
+    }
+});
@@ -0,0 +1,17 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use stellar_badge_wasm::util::proof_encoding::{self, SignedMessage};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = proof_encoding::verify_albedo_signed_message(&base64::encode(data));
+    if let Ok(blob) = std::str::from_utf8(data) {
+        let _ = proof_encoding::verify_albedo_signed_message(&String::from(blob));
+        if let Ok(signed) = SignedMessage::parse(&String::from(blob)) {
+            let _ = signed.verify();
+        }
+    }
+    let envelope = base64::encode_config(data, base64::URL_SAFE_NO_PAD);
+    if let Ok(signed) = SignedMessage::parse(&envelope) {
+        let _ = signed.verify();
+    }
+});
@@ -0,0 +1,123 @@
+//! Renders the badge wall of an account outside of the browser, as SVG and as PNG.
+//!
+//! ```text
+//! cargo run --features cli --bin badge_wall -- <account> <badges> <output>
+//! ```
+//!
+//! `badges` lists one badge per line, `CODE,IMAGE` for an owned badge and `CODE` alone for
+//! one that is still missing. Local image files are embedded, urls are only referenced and
+//! left out of the PNG. The wall is written to `<output>.svg` and `<output>.png`.
+use std::collections::HashMap;
+use std::path::Path;
+
+use stellar_badge_wasm::stellar::stellar_data::TOMLCurrency;
+use stellar_badge_wasm::util::badge_check::Badge;
+use stellar_badge_wasm::util::badge_wall;
+use stellar_badge_wasm::util::error::Error;
+use stellar_badge_wasm::util::svg;
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    if args.len() != 3 {
+        eprintln!("usage: badge_wall <account> <badges> <output>");
+        std::process::exit(2);
+    }
+    if let Err(err) = run(&args[0], Path::new(&args[1]), Path::new(&args[2])) {
+        eprintln!("{}", err.report());
+        std::process::exit(1);
+    }
+}
+
+fn run(account: &str, list: &Path, output: &Path) -> Result<(), Error> {
+    let text = std::fs::read_to_string(list)
+        .map_err(|err| Error::Other(format!("Reading {}: {}", list.display(), err)))?;
+    let badges = parse_badges(account, &text);
+
+    // image paths are relative to the badge list
+    let base = list.parent().unwrap_or_else(|| Path::new("."));
+    let mut images = HashMap::new();
+    for badge in badge_wall::wall_badges(&badges) {
+        let url = &badge.token.image;
+        if url.starts_with("http://") || url.starts_with("https://") {
+            continue;
+        }
+        let bytes = std::fs::read(base.join(url))
+            .map_err(|err| Error::Other(format!("Reading {}: {}", url, err)))?;
+        images.insert(
+            url.clone(),
+            svg::data_url(badge_wall::image_mime(url), &bytes),
+        );
+    }
+    let wall = badge_wall::render(account, &badges, &images);
+
+    let svg_path = output.with_extension("svg");
+    std::fs::write(&svg_path, &wall)
+        .map_err(|err| Error::Other(format!("Writing {}: {}", svg_path.display(), err)))?;
+    let png_path = output.with_extension("png");
+    let png = rasterize(&wall)?;
+    std::fs::write(&png_path, png)
+        .map_err(|err| Error::Other(format!("Writing {}: {}", png_path.display(), err)))?;
+    Ok(())
+}
+
+/// Badges of the `CODE,IMAGE` and `CODE` lines, empty lines and `#` comments are skipped
+fn parse_badges(account: &str, text: &str) -> Vec<Badge> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (code, image) = match line.split_once(',') {
+                Some((code, image)) => (code.trim(), Some(image.trim())),
+                None => (line, None),
+            };
+            Badge {
+                token: TOMLCurrency {
+                    code: String::from(code),
+                    image: String::from(image.unwrap_or_default()),
+                    ..Default::default()
+                },
+                owned: image.is_some(),
+                account: String::from(account),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+/// PNG of the wall, the badge images have to be embedded to show up
+fn rasterize(wall: &str) -> Result<Vec<u8>, Error> {
+    let mut options = resvg::usvg::Options::default();
+    let fonts = options.fontdb_mut();
+    fonts.load_system_fonts();
+    // the generic families default to fonts like Arial, fall back to any installed one
+    let generic = fonts.query(&resvg::usvg::fontdb::Query {
+        families: &[resvg::usvg::fontdb::Family::SansSerif],
+        ..Default::default()
+    });
+    let families = fonts
+        .faces()
+        .map(|face| face.families[0].0.clone())
+        .collect::<Vec<String>>();
+    let fallback = families
+        .iter()
+        .find(|family| family.ends_with(" Sans"))
+        .or_else(|| families.first())
+        .cloned();
+    if let (None, Some(family)) = (generic, fallback) {
+        fonts.set_sans_serif_family(family.clone());
+        fonts.set_monospace_family(family);
+    }
+    let tree = resvg::usvg::Tree::from_str(wall, &options)
+        .map_err(|err| Error::Other(format!("Parsing the badge wall: {}", err)))?;
+    let size = tree.size().to_int_size();
+    let mut pixmap = resvg::tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or_else(|| Error::Other(String::from("The badge wall is empty")))?;
+    resvg::render(
+        &tree,
+        resvg::tiny_skia::Transform::default(),
+        &mut pixmap.as_mut(),
+    );
+    pixmap
+        .encode_png()
+        .map_err(|err| Error::Other(format!("Encoding the badge wall: {}", err)))
+}
@@ -1,6 +1,6 @@
 use wasm_bindgen::prelude::*;
 //use wasm_bindgen_futures::JsFuture;
-use js_sys::{Boolean, JsString};
+use js_sys::JsString;
 
 #[wasm_bindgen(module = "/src/js/albedo.js")]
 extern "C" {
@@ -13,10 +13,12 @@ extern "C" {
         message: JsString,
         pubKey: JsString,
     ) -> Result<JsValue, JsValue>;
-    #[wasm_bindgen(js_name = "albedo_verify_message_signature", catch)]
-    pub fn albedo_verify_message_signature(
-        public_key: JsString,
-        plain_message: JsString,
-        message_signature: JsString,
-    ) -> Result<Boolean, JsValue>;
+    #[wasm_bindgen(js_name = "albedo_sign_transaction", catch)]
+    pub async fn sign_transaction(
+        xdr: JsString,
+        pubkey: JsString,
+        network: JsString,
+        callback: JsString,
+        submit: bool,
+    ) -> Result<JsValue, JsValue>;
 }
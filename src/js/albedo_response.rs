@@ -13,3 +13,12 @@ pub struct AlbedoPublicKey {
     pub signature: String,
     pub intent: String,
 }
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AlbedoSignedMessage {
+    pub pubkey: String,
+    pub message: String,
+    pub message_signature: String,
+    pub signed_message: String,
+    pub intent: String,
+}
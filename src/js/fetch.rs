@@ -5,33 +5,63 @@ use web_sys::{Request, RequestInit, RequestMode, Response};
 
 #[wasm_bindgen]
 pub async fn get(url: String) -> Result<Response, JsValue> {
-    let mut opts = RequestInit::new();
-    opts.method("GET");
-    opts.mode(RequestMode::Cors);
+    let opts = RequestInit::new();
+    opts.set_method("GET");
+    opts.set_mode(RequestMode::Cors);
 
-    let request = Request::new_with_str_and_init(&url, &opts).unwrap();
+    let request = Request::new_with_str_and_init(&url, &opts)?;
 
-    let window = web_sys::window().unwrap();
+    let window = web_sys::window().ok_or(JsValue::from_str("no window available"))?;
     let request_promise = window.fetch_with_request(&request);
 
     let future = JsFuture::from(request_promise).await?;
-    let resp: Response = future.dyn_into().unwrap();
+    let resp: Response = future.dyn_into()?;
     Ok(resp)
 }
 
-pub async fn get_text(url: &String) -> Result<String, JsValue> {
+pub async fn get_text(url: &str) -> Result<String, JsValue> {
     let resp = get(url.to_string()).await?;
     let text = JsFuture::from(resp.text()?)
-        .await
-        .unwrap()
+        .await?
         .as_string()
-        .unwrap();
+        .ok_or(JsValue::from_str("response is not a text"))?;
     Ok(text)
 }
 
-pub async fn get_json(url: &String) -> Result<JsValue, JsValue> {
+pub async fn get_json(url: &str) -> Result<JsValue, JsValue> {
     let resp = get(url.to_string()).await?;
-    let value = JsFuture::from(resp.json()?).await.unwrap();
+    let value = JsFuture::from(resp.json()?).await?;
 
     Ok(value)
 }
+
+/// Posts an url encoded form, e.g. a transaction to horizon
+pub async fn post_form(url: &str, body: &str) -> Result<JsValue, JsValue> {
+    let opts = RequestInit::new();
+    opts.set_method("POST");
+    opts.set_mode(RequestMode::Cors);
+    opts.set_body(&JsValue::from_str(body));
+
+    let request = Request::new_with_str_and_init(url, &opts)?;
+    request
+        .headers()
+        .set("Content-Type", "application/x-www-form-urlencoded")?;
+
+    let window = web_sys::window().ok_or(JsValue::from_str("no window available"))?;
+    let resp: Response = JsFuture::from(window.fetch_with_request(&request))
+        .await?
+        .dyn_into()?;
+    let value = JsFuture::from(resp.json()?).await?;
+
+    Ok(value)
+}
+
+/// The raw body of a response, e.g. an image to embed
+pub async fn get_bytes(url: &str) -> Result<Vec<u8>, JsValue> {
+    let resp = get(url.to_string()).await?;
+    if !resp.ok() {
+        return Err(JsValue::from_str(&format!("status {}", resp.status())));
+    }
+    let buffer = JsFuture::from(resp.array_buffer()?).await?;
+    Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+}
@@ -0,0 +1,65 @@
+// This file is included in freighter.rs
+
+const NOT_INSTALLED = -1;
+const REJECTED = -2;
+
+function freighter() {
+    if (typeof window.freighterApi === "undefined") {
+        throw { code: NOT_INSTALLED, message: "Freighter is not installed" };
+    }
+    return window.freighterApi;
+}
+
+// Freighter resolves with an `error` field or rejects with plain strings,
+// bring both into the same shape as albedo errors
+async function call(f) {
+    const api = freighter();
+    const connected = await api.isConnected();
+    if (!connected || !connected.isConnected) {
+        throw { code: NOT_INSTALLED, message: "Freighter is not installed" };
+    }
+    let result;
+    try {
+        result = await f(api);
+    } catch (e) {
+        throw { code: REJECTED, message: (e && e.message) || String(e) };
+    }
+    if (result && result.error) {
+        throw { code: REJECTED, message: result.error.message || String(result.error) };
+    }
+    return result;
+}
+
+function toHex(signature) {
+    let bytes;
+    if (typeof signature === "string") {
+        bytes = Uint8Array.from(atob(signature), (c) => c.charCodeAt(0));
+    } else if (signature && signature.data) {
+        bytes = Uint8Array.from(signature.data);
+    } else {
+        bytes = Uint8Array.from(signature);
+    }
+    return Array.from(bytes, (b) => b.toString(16).padStart(2, "0")).join("");
+}
+
+export async function freighter_public_key() {
+    const result = await call((api) => api.getAddress());
+    return result.address;
+}
+
+// Freighter signs messages according to SEP-53
+export async function freighter_sign_message(message, pubkey) {
+    const signed = await call((api) => api.signMessage(message, { address: pubkey }));
+    return {
+        pubkey: signed.signerAddress || pubkey,
+        message: message,
+        message_signature: toHex(signed.signedMessage),
+    };
+}
+
+export async function freighter_sign_transaction(xdr, pubkey, network_passphrase) {
+    const signed = await call((api) =>
+        api.signTransaction(xdr, { networkPassphrase: network_passphrase, address: pubkey })
+    );
+    return signed.signedTxXdr;
+}
@@ -0,0 +1,16 @@
+use js_sys::JsString;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(module = "/src/js/freighter.js")]
+extern "C" {
+    #[wasm_bindgen(js_name = "freighter_public_key", catch)]
+    pub async fn public_key() -> Result<JsValue, JsValue>;
+    #[wasm_bindgen(js_name = "freighter_sign_message", catch)]
+    pub async fn sign_message(message: JsString, pubkey: JsString) -> Result<JsValue, JsValue>;
+    #[wasm_bindgen(js_name = "freighter_sign_transaction", catch)]
+    pub async fn sign_transaction(
+        xdr: JsString,
+        pubkey: JsString,
+        network_passphrase: JsString,
+    ) -> Result<JsValue, JsValue>;
+}
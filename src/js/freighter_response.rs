@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/// Error thrown by `freighter.js`, `code` -1 if the extension is missing
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FreighterError {
+    pub code: i64,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FreighterSignedMessage {
+    /// SEP-53 signer of the message
+    pub pubkey: String,
+    pub message: String,
+    pub message_signature: String,
+}
@@ -1,3 +1,5 @@
 pub mod albedo;
 pub mod albedo_response;
 pub mod fetch;
+pub mod freighter;
+pub mod freighter_response;
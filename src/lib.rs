@@ -0,0 +1,4 @@
+pub mod js;
+pub mod stellar;
+pub mod util;
+pub mod webpage;
@@ -1,8 +1,3 @@
-mod js;
-mod stellar;
-mod util;
-mod webpage;
-
 fn main() {
-    webpage::view::start();
+    stellar_badge_wasm::webpage::view::start();
 }
@@ -1,2 +1,5 @@
+#[allow(clippy::module_inception)]
 pub mod stellar;
 pub mod stellar_data;
+pub mod strkey;
+pub mod transaction;
@@ -1,153 +1,300 @@
 use crate::js::fetch;
 use crate::stellar::stellar_data;
 use crate::util::error::{Error, StellarErr};
+use gloo_utils::format::JsValueSerdeExt;
 use log::debug;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use toml::Value as tomlValue;
-use wasm_bindgen::JsValue;
-use web_sys::console::debug;
 static HORIZONT_ENDPOINT: &str = "https://horizon.stellar.org/";
 
+thread_local! {
+    static ENDPOINT: RefCell<String> = RefCell::new(String::from(HORIZONT_ENDPOINT));
+}
+
+/// Points all requests to another horizon instance (e.g. a local fake one for testing)
+pub fn set_horizon_endpoint(url: &str) {
+    ENDPOINT.with(|e| *e.borrow_mut() = String::from(url));
+}
+
+fn horizon_endpoint() -> String {
+    ENDPOINT.with(|e| e.borrow().clone())
+}
+
 type Result<T> = std::result::Result<T, Error>;
 
-#[allow(dead_code)]
-pub async fn fetch_account(id: &String) -> Result<stellar_data::Account> {
-    let mut url = String::from(HORIZONT_ENDPOINT);
-    url.push_str("accounts/");
-    url.push_str(&id);
-    let json = fetch::get_json(&url).await;
+/// Maps horizons problem responses to an error
+fn status_error(data: &Value) -> Error {
+    match data.pointer("/status").and_then(|s| s.as_u64()) {
+        // invalid public key?
+        Some(400) => Error::StellarErr(StellarErr::InvalidPublicKey),
+        // account not funded
+        Some(404) => Error::StellarErr(StellarErr::AccountNotFound),
+        _ => Error::StellarErr(StellarErr::Unknown),
+    }
+}
+
+/// Fetches a json document from horizon
+async fn fetch_value(url: &str) -> Result<Value> {
+    let json = fetch::get_json(url).await.map_err(|err| {
+        debug!("fetching {} failed: {:?}", url, err);
+        Error::StellarErr(StellarErr::NetworkError)
+    })?;
+    JsValueSerdeExt::into_serde(&json).map_err(|_| Error::StellarErr(StellarErr::InvalidResponse))
+}
 
-    if json.is_err() {
-        return Err(Error::Other(json.err().unwrap().as_string().unwrap()));
+/// Splits a page of a horizon collection into its records and the link to the next page
+fn parse_page<T: DeserializeOwned>(data: &Value) -> Result<(Vec<T>, String)> {
+    let next = match data.pointer("/_links/next/href").and_then(|n| n.as_str()) {
+        Some(next) => urldecode::decode(String::from(next)),
+        None => return Err(status_error(data)),
+    };
+    let records = data
+        .pointer("/_embedded/records")
+        .cloned()
+        .ok_or(Error::StellarErr(StellarErr::InvalidResponse))?;
+    let records = serde_json::from_value(records)
+        .map_err(|_| Error::StellarErr(StellarErr::InvalidResponse))?;
+    Ok((records, next))
+}
+
+pub async fn fetch_account(id: &str) -> Result<stellar_data::Account> {
+    let mut url = horizon_endpoint();
+    url.push_str("accounts/");
+    url.push_str(id);
+    let data = fetch_value(&url).await?;
+    if data.pointer("/status").is_some() {
+        return Err(status_error(&data));
     }
 
-    let acc: stellar_data::Account = json.unwrap().into_serde().unwrap();
-    Ok(acc)
+    serde_json::from_value(data).map_err(|_| Error::StellarErr(StellarErr::InvalidResponse))
 }
 
-pub async fn fetch_account_payments(id: &String) -> Result<Vec<stellar_data::OperationPayment>> {
-    let mut url = String::from(HORIZONT_ENDPOINT);
+pub async fn fetch_account_payments(id: &str) -> Result<Vec<stellar_data::OperationPayment>> {
+    let mut url = horizon_endpoint();
     url.push_str("accounts/");
-    url.push_str(&id);
+    url.push_str(id);
     url.push_str("/payments?limit=200");
 
     let mut next_url = url.clone();
     let mut all_payments = vec![];
     loop {
-        let json = fetch::get_json(&next_url).await;
-        if json.is_err() {
+        let data = fetch_value(&next_url).await?;
+        let (mut payment_data, next): (Vec<stellar_data::OperationPayment>, String) =
+            parse_page(&data)?;
+
+        next_url = next;
+        if payment_data.is_empty() {
             break;
         }
+        all_payments.append(&mut payment_data);
+    }
+    Ok(all_payments)
+}
 
-        let data: Value = json.ok().unwrap().into_serde().unwrap();
-        let next = data.pointer("/_links/next/href");
+/// Fetches the payments of `id` involving one of `trusted` or `watched` (`CODE:ISSUER`),
+/// oldest first, from the operations of `id` read newest first.
+///
+/// A payment can not be older than the trustline of its asset, so paging stops once the
+/// creation of the trustline of every `trusted` asset has been passed. The removal of a
+/// `watched` trustline on the way reopens its asset until its creation is passed as well.
+/// Horizon does not tell trustline creations from limit changes, any change to a non zero
+/// limit is taken as creation (badge trustlines keep the maximum limit).
+pub async fn fetch_account_payments_for_assets(
+    id: &str,
+    trusted: &[String],
+    watched: &[String],
+) -> Result<Vec<stellar_data::OperationPayment>> {
+    let mut url = horizon_endpoint();
+    url.push_str("accounts/");
+    url.push_str(id);
+    url.push_str("/operations?limit=200&order=desc");
 
-        if next.is_none() {
-            let status_code = data.pointer("/status");
-            if status_code.is_none() {
-                return Err(Error::StellarErr(StellarErr::Unknown));
-            }
-            let status_code = status_code.unwrap();
-            if status_code == 400 {
-                // invalid public key?
-                return Err(Error::StellarErr(StellarErr::InvalidPublicKey));
-            } else if status_code == 404 {
-                // account not funded
-                return Err(Error::StellarErr(StellarErr::AccountNotFound));
-            }
-            return Err(Error::StellarErr(StellarErr::Unknown));
-        }
+    let mut open = trusted.iter().cloned().collect::<HashSet<String>>();
+    let mut next_url = url.clone();
+    let mut asset_payments = vec![];
+    while !open.is_empty() {
+        let data = fetch_value(&next_url).await?;
+        let (operation_data, next): (Vec<stellar_data::AccountOperation>, String) =
+            parse_page(&data)?;
 
-        next_url = urldecode::decode(String::from(next.unwrap().as_str().unwrap()));
-        let records = data.pointer("/_embedded/records").unwrap().clone();
-        let mut payment_data: Vec<stellar_data::OperationPayment> =
-            serde_json::from_value(records).unwrap();
-        if payment_data.len() == 0 {
+        next_url = next;
+        if operation_data.is_empty() {
             break;
         }
-        all_payments.append(&mut payment_data);
+
+        for operation in operation_data {
+            let asset = operation.asset();
+            if !trusted.contains(&asset) && !watched.contains(&asset) {
+                continue;
+            }
+            if operation.is_payment() {
+                asset_payments.push(operation.payment);
+            } else if operation.kind == "change_trust" && operation.trustor == id {
+                match operation.removes_trustline() {
+                    true => open.insert(asset),
+                    false => open.remove(&asset),
+                };
+                // the rest of the page is older than every trustline
+                if open.is_empty() {
+                    break;
+                }
+            }
+        }
     }
-    Ok(all_payments)
+    debug!("{}: read {} badge payments", id, asset_payments.len());
+    // keep the chronological order of the full scan
+    asset_payments.reverse();
+    Ok(asset_payments)
 }
 
+/// Scans the operations of `issuer` once for claimable balances created for `needle_account`.
+/// Returns the latest matching operation for each of the requested `assets`.
 pub async fn search_created_claimed_balances(
-    issuer: &String,
-    asset: &String,
-    needle_account: &String,
-) -> Option<stellar_data::OperationClaimableBalance> {
-    debug!("Searching for asset {} in claimable balances", asset);
-    let mut url = String::from(HORIZONT_ENDPOINT);
+    issuer: &str,
+    assets: &[String],
+    needle_account: &str,
+) -> HashMap<String, stellar_data::OperationClaimableBalance> {
+    debug!("Searching for assets {:?} in claimable balances", assets);
+    let mut url = horizon_endpoint();
     url.push_str("accounts/");
-    url.push_str(&issuer);
+    url.push_str(issuer);
     url.push_str("/operations?limit=200&order=desc");
 
+    let mut found = HashMap::new();
     let mut next_url = url.clone();
     loop {
-        let json = fetch::get_json(&next_url).await;
-        if json.is_err() {
+        if assets.iter().all(|a| found.contains_key(a)) {
             break;
         }
 
-        let data: Value = json.ok().unwrap().into_serde().unwrap();
-        let next = data.pointer("/_links/next/href");
-
-        if next.is_none() {
-            return None;
-        }
+        // best effort, a failing scan only means the claimable balance is not found
+        let page = match fetch_value(&next_url).await {
+            Ok(data) => parse_page(&data),
+            Err(err) => Err(err),
+        };
+        let (operation_data, next): (Vec<stellar_data::OperationClaimableBalance>, String) =
+            match page {
+                Ok(page) => page,
+                Err(err) => {
+                    debug!("scanning operations of {} failed: {:?}", issuer, err);
+                    break;
+                }
+            };
 
-        next_url = urldecode::decode(String::from(next.unwrap().as_str().unwrap()));
-        let records = data.pointer("/_embedded/records").unwrap().clone();
-        let operation_data: Vec<stellar_data::OperationClaimableBalance> =
-            serde_json::from_value(records).unwrap();
-        if operation_data.len() == 0 {
-            return None;
+        next_url = next;
+        if operation_data.is_empty() {
+            break;
         }
 
         for operation in operation_data {
-            let op_clone = operation.clone();
             if operation.type_i != 14 {
                 // is not claimable balance
                 continue;
             }
 
-            if &operation.asset != asset {
+            if !assets.contains(&operation.asset) || found.contains_key(&operation.asset) {
                 continue;
             }
 
-            let claimants = operation.claimants;
-
-            for c in claimants {
-                if c.is_object() {
-                    let c = c.as_object().unwrap();
-                    if c.contains_key("destination")
-                        && c.get("destination").unwrap().as_str().unwrap()
-                            == needle_account.as_str()
-                    {
-                        return Some(op_clone);
-                    }
-                }
+            let for_needle = operation
+                .claimants
+                .iter()
+                .any(|c| c.get("destination").and_then(|d| d.as_str()) == Some(needle_account));
+
+            if for_needle {
+                found.insert(operation.asset.clone(), operation);
             }
         }
     }
-    None
+    found
+}
+
+pub async fn fetch_account_claimable_balances(
+    id: &str,
+) -> Result<Vec<stellar_data::ClaimableBalance>> {
+    let mut url = horizon_endpoint();
+    url.push_str("claimable_balances?claimant=");
+    url.push_str(id);
+    url.push_str("&limit=200");
+
+    let mut next_url = url.clone();
+    let mut all_balances = vec![];
+    loop {
+        let data = fetch_value(&next_url).await?;
+        let (mut balance_data, next): (Vec<stellar_data::ClaimableBalance>, String) =
+            parse_page(&data)?;
+
+        next_url = next;
+        if balance_data.is_empty() {
+            break;
+        }
+        all_balances.append(&mut balance_data);
+    }
+    Ok(all_balances)
 }
 
 #[allow(dead_code)]
-pub async fn fetch_ledger_payments(
-    id: &String,
-) -> std::result::Result<Vec<stellar_data::OperationPayment>, JsValue> {
-    let mut url = String::from(HORIZONT_ENDPOINT);
+pub async fn fetch_ledger_payments(id: &str) -> Result<Vec<stellar_data::OperationPayment>> {
+    let mut url = horizon_endpoint();
     url.push_str("ledgers/");
-    url.push_str(&id);
+    url.push_str(id);
     url.push_str("/payments");
-    let json = fetch::get_json(&url).await;
-    let obj: Value = json?.into_serde().unwrap();
-    let records = obj.pointer("/_embedded/records").unwrap().clone();
-    let payments: Vec<stellar_data::OperationPayment> = serde_json::from_value(records).unwrap();
+    let data = fetch_value(&url).await?;
+    let (payments, _) = parse_page(&data)?;
     Ok(payments)
 }
 
-pub async fn fetch_toml_currencies(toml_url: &String) -> Option<Vec<stellar_data::TOMLCurrency>> {
+pub async fn fetch_transaction(hash: &str) -> Result<stellar_data::Transaction> {
+    let mut url = horizon_endpoint();
+    url.push_str("transactions/");
+    url.push_str(hash);
+    let data = fetch_value(&url).await?;
+    if data.pointer("/status").is_some() {
+        return Err(status_error(&data));
+    }
+
+    serde_json::from_value(data).map_err(|_| Error::StellarErr(StellarErr::InvalidResponse))
+}
+
+/// Fetches the operations of the transaction `hash`, transactions hold at most 100 of them
+pub async fn fetch_transaction_operations(hash: &str) -> Result<Vec<stellar_data::Operation>> {
+    let mut url = horizon_endpoint();
+    url.push_str("transactions/");
+    url.push_str(hash);
+    url.push_str("/operations?limit=200");
+    let data = fetch_value(&url).await?;
+    let (operations, _) = parse_page(&data)?;
+    Ok(operations)
+}
+
+/// Submits a signed transaction envelope, returns the transaction hash
+pub async fn submit_transaction(xdr: &str) -> Result<String> {
+    let mut url = horizon_endpoint();
+    url.push_str("transactions");
+    let body = format!("tx={}", js_sys::encode_uri_component(xdr));
+    let resp = fetch::post_form(&url, &body).await.map_err(|err| {
+        debug!("submitting transaction failed: {:?}", err);
+        Error::StellarErr(StellarErr::NetworkError)
+    })?;
+    let data: Value = JsValueSerdeExt::into_serde(&resp)
+        .map_err(|_| Error::StellarErr(StellarErr::InvalidResponse))?;
+
+    match data.pointer("/hash").and_then(|h| h.as_str()) {
+        Some(hash) => Ok(String::from(hash)),
+        None => {
+            debug!(
+                "transaction rejected: {:?}",
+                data.pointer("/extras/result_codes")
+            );
+            Err(Error::StellarErr(StellarErr::TransactionFailed))
+        }
+    }
+}
+
+pub async fn fetch_toml_currencies(toml_url: &str) -> Option<Vec<stellar_data::TOMLCurrency>> {
     let data = match fetch::get_text(toml_url).await {
         Ok(s) => s,
         Err(_) => {
@@ -166,8 +313,38 @@ pub async fn fetch_toml_currencies(toml_url: &String) -> Option<Vec<stellar_data
     let currencies: std::result::Result<Vec<stellar_data::TOMLCurrency>, toml::de::Error> =
         currencies.try_into();
 
-    match currencies {
-        Ok(s) => Some(s),
-        Err(_) => None,
-    }
+    currencies.ok()
+}
+
+/// All fields of the `[[CURRENCIES]]` entry of `code` issued by `issuer`, rendered as strings
+pub async fn fetch_toml_currency_metadata(
+    toml_url: &str,
+    code: &str,
+    issuer: &str,
+) -> Option<Vec<(String, String)>> {
+    let data = fetch::get_text(toml_url).await.ok()?;
+    let val: tomlValue = toml::from_str(&data).ok()?;
+
+    let currency = val
+        .get("CURRENCIES")?
+        .as_array()?
+        .iter()
+        .filter_map(|c| c.as_table())
+        .find(|c| {
+            c.get("code").and_then(|v| v.as_str()) == Some(code)
+                && c.get("issuer").and_then(|v| v.as_str()) == Some(issuer)
+        })?;
+
+    Some(
+        currency
+            .iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    tomlValue::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                (key.clone(), value)
+            })
+            .collect(),
+    )
 }
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Debug, Default)]
 #[serde(default)]
@@ -7,6 +8,8 @@ pub struct Account {
     pub account_id: String,
     pub sequence: String,
     pub balances: Vec<Balance>,
+    /// manage_data entries, the values base64 encoded
+    pub data: HashMap<String, String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
@@ -19,7 +22,7 @@ pub struct Balance {
     pub last_modified_ledger: u64,
 }
 
-#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
 #[serde(default)]
 pub struct OperationPayment {
     pub id: String,
@@ -33,6 +36,40 @@ pub struct OperationPayment {
     pub to: String,
 }
 
+/// An operation from the history of an account, only payments and trustline changes are read
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct AccountOperation {
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// account changing a trustline, for `change_trust`
+    pub trustor: String,
+    /// new limit of the trustline, for `change_trust`
+    pub limit: String,
+    #[serde(flatten)]
+    pub payment: OperationPayment,
+}
+
+impl AccountOperation {
+    /// Asset in horizons `CODE:ISSUER` notation, `:` for native and operations without asset
+    pub fn asset(&self) -> String {
+        format!("{}:{}", self.payment.asset_code, self.payment.asset_issuer)
+    }
+
+    /// Whether the operation moves the asset from `from` to `to`
+    pub fn is_payment(&self) -> bool {
+        matches!(
+            self.kind.as_str(),
+            "payment" | "path_payment_strict_receive" | "path_payment_strict_send"
+        )
+    }
+
+    /// Whether a `change_trust` removes the trustline
+    pub fn removes_trustline(&self) -> bool {
+        self.limit.parse::<f64>().unwrap_or(0.0) == 0.0
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 #[serde(default)]
 pub struct OperationClaimableBalance {
@@ -53,3 +90,56 @@ pub struct TOMLCurrency {
     pub image: String,
     pub tag: String,
 }
+
+impl TOMLCurrency {
+    /// Asset in horizons `CODE:ISSUER` notation
+    pub fn asset(&self) -> String {
+        format!("{}:{}", self.code, self.issuer)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct ClaimableBalance {
+    pub id: String,
+    pub asset: String,
+    pub amount: String,
+    pub sponsor: String,
+    pub last_modified_ledger: u64,
+    pub claimants: Vec<Value>,
+}
+
+impl ClaimableBalance {
+    /// Whether `account` is one of the claimants of the balance
+    pub fn claimable_by(&self, account: &str) -> bool {
+        self.claimants
+            .iter()
+            .any(|c| c.get("destination").and_then(|d| d.as_str()) == Some(account))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct Transaction {
+    pub hash: String,
+    pub ledger: u64,
+    pub created_at: String,
+    pub source_account: String,
+    pub successful: bool,
+    pub memo_type: String,
+    /// base64 for `hash` and `return` memos
+    pub memo: Option<String>,
+    pub fee_charged: String,
+    pub operation_count: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct Operation {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+    pub source_account: String,
+    pub created_at: String,
+    pub transaction_hash: String,
+}
@@ -0,0 +1,46 @@
+/// Version byte of `G...` account ids
+pub const VERSION_ACCOUNT_ID: u8 = 6 << 3;
+/// Version byte of `S...` secret seeds
+pub const VERSION_SEED: u8 = 18 << 3;
+
+const ALPHABET: base32::Alphabet = base32::Alphabet::RFC4648 { padding: false };
+
+/// CRC16-XModem, the checksum appended to every strkey
+fn checksum(data: &[u8]) -> [u8; 2] {
+    let mut crc: u16 = 0;
+    for byte in data {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc.to_le_bytes()
+}
+
+/// Encodes a 32 byte key as strkey with the given version byte
+pub fn encode(version: u8, key: &[u8; 32]) -> String {
+    let mut raw = Vec::with_capacity(35);
+    raw.push(version);
+    raw.extend_from_slice(key);
+    let crc = checksum(&raw);
+    raw.extend_from_slice(&crc);
+    base32::encode(ALPHABET, &raw)
+}
+
+/// Decodes a strkey, None if version byte or checksum do not match
+pub fn decode(version: u8, strkey: &str) -> Option<[u8; 32]> {
+    let raw = base32::decode(ALPHABET, strkey)?;
+
+    // version byte + 32 byte key + 2 byte checksum
+    if raw.len() != 35 || raw[0] != version || checksum(&raw[..33]) != raw[33..] {
+        return None;
+    }
+
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&raw[1..33]);
+    Some(key)
+}
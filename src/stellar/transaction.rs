@@ -0,0 +1,238 @@
+use crate::stellar::strkey;
+use crate::util::error::{Error, StellarErr};
+use sha2::{Digest, Sha256};
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Passphrase of the public network, hashed into every transaction signature
+pub const PUBLIC_NETWORK_PASSPHRASE: &str = "Public Global Stellar Network ; September 2015";
+
+const ENVELOPE_TYPE_TX: i32 = 2;
+const KEY_TYPE_ED25519: i32 = 0;
+const ASSET_TYPE_CREDIT_ALPHANUM4: i32 = 1;
+const ASSET_TYPE_CREDIT_ALPHANUM12: i32 = 2;
+const MEMO_HASH: i32 = 3;
+const OP_CHANGE_TRUST: i32 = 6;
+const OP_MANAGE_DATA: i32 = 10;
+const OP_BUMP_SEQUENCE: i32 = 11;
+const OP_CLAIM_CLAIMABLE_BALANCE: i32 = 15;
+const BASE_FEE: u32 = 100;
+/// Maximum length of manage_data names and values
+pub const MAX_DATA_LENGTH: usize = 64;
+
+/// Minimal XDR writer, only covering what is needed to build the transactions below
+#[derive(Default)]
+struct XdrWriter {
+    buf: Vec<u8>,
+}
+
+impl XdrWriter {
+    fn int32(&mut self, v: i32) {
+        self.buf.extend_from_slice(&v.to_be_bytes());
+    }
+    fn uint32(&mut self, v: u32) {
+        self.buf.extend_from_slice(&v.to_be_bytes());
+    }
+    fn int64(&mut self, v: i64) {
+        self.buf.extend_from_slice(&v.to_be_bytes());
+    }
+    fn opaque(&mut self, v: &[u8]) {
+        self.buf.extend_from_slice(v);
+    }
+    /// Length prefixed and padded to a multiple of 4 bytes, for `string<>` and `opaque<>`
+    fn var_opaque(&mut self, v: &[u8]) {
+        self.uint32(v.len() as u32);
+        self.opaque(v);
+        self.opaque(&[0u8; 3][..(4 - v.len() % 4) % 4]);
+    }
+    fn public_key(&mut self, key: &[u8; 32]) {
+        self.int32(KEY_TYPE_ED25519);
+        self.opaque(key);
+    }
+    /// Credit asset, alphanum4 for codes of up to 4 characters and alphanum12 for longer ones
+    fn asset(&mut self, code: &str, issuer: &[u8; 32]) -> Result<()> {
+        let (asset_type, width) = match code.len() {
+            1..=4 => (ASSET_TYPE_CREDIT_ALPHANUM4, 4),
+            5..=12 => (ASSET_TYPE_CREDIT_ALPHANUM12, 12),
+            _ => return Err(Error::Other(format!("Invalid asset code `{}`", code))),
+        };
+        if !code.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            return Err(Error::Other(format!("Invalid asset code `{}`", code)));
+        }
+        let mut padded = [0u8; 12];
+        padded[..code.len()].copy_from_slice(code.as_bytes());
+        self.int32(asset_type);
+        self.opaque(&padded[..width]);
+        self.public_key(issuer);
+        Ok(())
+    }
+}
+
+/// Decodes a `G...` strkey into the raw ed25519 public key
+pub fn decode_public_key(account: &str) -> Result<[u8; 32]> {
+    strkey::decode(strkey::VERSION_ACCOUNT_ID, account)
+        .ok_or(Error::StellarErr(StellarErr::InvalidPublicKey))
+}
+
+/// Builds an unsigned transaction claiming the claimable balance `balance_id`.
+/// If the account has no trustline for the asset yet, one is established in the same transaction.
+/// Returns the base64 encoded transaction envelope, ready to be signed.
+pub fn claim_claimable_balance(
+    account: &str,
+    sequence: &str,
+    balance_id: &str,
+    asset_code: &str,
+    asset_issuer: &str,
+    add_trustline: bool,
+) -> Result<String> {
+    let source = decode_public_key(account)?;
+    let sequence: i64 = sequence
+        .parse()
+        .map_err(|_| Error::Other(format!("Invalid sequence number `{}`", sequence)))?;
+    // horizon already returns the XDR encoded ClaimableBalanceID as hex
+    let balance_id = hex::decode(balance_id)
+        .map_err(|_| Error::Other(format!("Invalid claimable balance id `{}`", balance_id)))?;
+
+    let op_count = if add_trustline { 2 } else { 1 };
+
+    let mut w = XdrWriter::default();
+    w.int32(ENVELOPE_TYPE_TX);
+    // Transaction
+    w.public_key(&source); // MuxedAccount
+    w.uint32(BASE_FEE * op_count);
+    w.int64(sequence + 1);
+    w.int32(0); // no preconditions
+    w.int32(0); // MEMO_NONE
+    w.uint32(op_count);
+    if add_trustline {
+        w.int32(0); // no operation source account
+        w.int32(OP_CHANGE_TRUST);
+        w.asset(asset_code, &decode_public_key(asset_issuer)?)?;
+        w.int64(i64::MAX); // limit
+    }
+    w.int32(0); // no operation source account
+    w.int32(OP_CLAIM_CLAIMABLE_BALANCE);
+    w.opaque(&balance_id);
+    w.int32(0); // ext
+    w.uint32(0); // no signatures yet
+
+    Ok(base64::encode(w.buf))
+}
+
+/// Builds an unsigned transaction without any effect (bumping the sequence to 0)
+/// carrying `hash` as memo, to anchor it in the ledger.
+/// Returns the base64 encoded transaction envelope, ready to be signed.
+pub fn anchor_hash(account: &str, sequence: &str, hash: &[u8; 32]) -> Result<String> {
+    let source = decode_public_key(account)?;
+    let sequence: i64 = sequence
+        .parse()
+        .map_err(|_| Error::Other(format!("Invalid sequence number `{}`", sequence)))?;
+
+    let mut w = XdrWriter::default();
+    w.int32(ENVELOPE_TYPE_TX);
+    // Transaction
+    w.public_key(&source); // MuxedAccount
+    w.uint32(BASE_FEE);
+    w.int64(sequence + 1);
+    w.int32(0); // no preconditions
+    w.int32(MEMO_HASH);
+    w.opaque(hash);
+    w.uint32(1);
+    w.int32(0); // no operation source account
+    w.int32(OP_BUMP_SEQUENCE);
+    w.int64(0); // bump to, lower than the current sequence so nothing changes
+    w.int32(0); // ext
+    w.uint32(0); // no signatures yet
+
+    Ok(base64::encode(w.buf))
+}
+
+/// Builds an unsigned transaction setting the data entry `name` of the account,
+/// a `value` of None deletes the entry.
+/// Returns the base64 encoded transaction envelope, ready to be signed.
+pub fn manage_data(
+    account: &str,
+    sequence: &str,
+    name: &str,
+    value: Option<&[u8]>,
+) -> Result<String> {
+    let source = decode_public_key(account)?;
+    let sequence: i64 = sequence
+        .parse()
+        .map_err(|_| Error::Other(format!("Invalid sequence number `{}`", sequence)))?;
+    if name.is_empty()
+        || name.len() > MAX_DATA_LENGTH
+        || value.is_some_and(|v| v.len() > MAX_DATA_LENGTH)
+    {
+        return Err(Error::Other(format!("Invalid data entry `{}`", name)));
+    }
+
+    let mut w = XdrWriter::default();
+    w.int32(ENVELOPE_TYPE_TX);
+    // Transaction
+    w.public_key(&source); // MuxedAccount
+    w.uint32(BASE_FEE);
+    w.int64(sequence + 1);
+    w.int32(0); // no preconditions
+    w.int32(0); // no memo
+    w.uint32(1);
+    w.int32(0); // no operation source account
+    w.int32(OP_MANAGE_DATA);
+    w.var_opaque(name.as_bytes());
+    match value {
+        Some(value) => {
+            w.int32(1);
+            w.var_opaque(value);
+        }
+        None => w.int32(0),
+    }
+    w.int32(0); // ext
+    w.uint32(0); // no signatures yet
+
+    Ok(base64::encode(w.buf))
+}
+
+fn decode_unsigned_envelope(envelope: &str) -> Result<Vec<u8>> {
+    let raw = base64::decode(envelope)
+        .map_err(|_| Error::Other(String::from("Invalid transaction envelope")))?;
+    // envelope type + transaction + empty signature list
+    if raw.len() < 8
+        || raw[..4] != ENVELOPE_TYPE_TX.to_be_bytes()
+        || raw[raw.len() - 4..] != 0u32.to_be_bytes()
+    {
+        return Err(Error::Other(String::from(
+            "Only unsigned transaction envelopes can be signed",
+        )));
+    }
+    Ok(raw)
+}
+
+/// The bytes whose sha256 hash has to be signed: network id, envelope type and transaction
+pub fn signature_base(envelope: &str, network_id: &[u8; 32]) -> Result<Vec<u8>> {
+    let raw = decode_unsigned_envelope(envelope)?;
+    let mut base = network_id.to_vec();
+    base.extend_from_slice(&raw[..raw.len() - 4]);
+    Ok(base)
+}
+
+/// Hash identifying the transaction on the network, as hex like horizon shows it
+pub fn transaction_hash(envelope: &str, network_passphrase: &str) -> Result<String> {
+    let mut network_id = [0u8; 32];
+    network_id.copy_from_slice(&Sha256::digest(network_passphrase.as_bytes()));
+    let base = signature_base(envelope, &network_id)?;
+    Ok(hex::encode(Sha256::digest(&base)))
+}
+
+/// Adds the signature of `signer` to an unsigned envelope built above
+pub fn add_signature(envelope: &str, signer: &[u8; 32], signature: &[u8; 64]) -> Result<String> {
+    let mut raw = decode_unsigned_envelope(envelope)?;
+    raw.truncate(raw.len() - 4);
+
+    let mut w = XdrWriter { buf: raw };
+    w.uint32(1);
+    w.opaque(&signer[28..]); // hint: last 4 bytes of the public key
+    w.uint32(64);
+    w.opaque(signature);
+
+    Ok(base64::encode(w.buf))
+}
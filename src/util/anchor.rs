@@ -0,0 +1,77 @@
+//! Anchors a proof on-chain: a transaction without effect whose hash memo commits to the proof.
+//!
+//! `Proof.timestamp` is chosen by the prover, the close time of the ledger including the
+//! anchor transaction is not.
+use crate::stellar::stellar_data::Transaction;
+use crate::stellar::{stellar, transaction};
+use crate::util::error::Error;
+use crate::util::proof_encoding::SignedMessage;
+use sha2::{Digest, Sha256};
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Result of looking up the anchor transaction of a proof
+#[derive(Debug, PartialEq, Clone)]
+pub enum Anchor {
+    /// The transaction commits to the proof, it existed at `closed_at` at the latest
+    Confirmed {
+        tx_hash: String,
+        ledger: u64,
+        closed_at: String,
+    },
+    /// The referenced transaction does not commit to this proof
+    Mismatch { tx_hash: String, reason: String },
+}
+
+/// The hash put into the memo of the anchor transaction
+pub fn proof_hash(message: &str) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&Sha256::digest(message.as_bytes()));
+    hash
+}
+
+/// Builds the unsigned anchor transaction for the proof `message` of `account`
+pub fn anchor_transaction(account: &str, sequence: &str, message: &str) -> Result<String> {
+    transaction::anchor_hash(account, sequence, &proof_hash(message))
+}
+
+/// Checks that `tx` is a successful transaction of the prover committing to the signed proof
+pub fn check(signed: &SignedMessage, tx: &Transaction) -> Anchor {
+    let mismatch = |reason: &str| Anchor::Mismatch {
+        tx_hash: tx.hash.clone(),
+        reason: String::from(reason),
+    };
+
+    if !tx.successful {
+        return mismatch("the anchor transaction failed");
+    }
+    if tx.source_account != signed.pubkey {
+        return mismatch("the anchor transaction was sent by another account");
+    }
+    let memo = match (tx.memo_type.as_str(), &tx.memo) {
+        ("hash", Some(memo)) => base64::decode(memo).unwrap_or_default(),
+        _ => return mismatch("the anchor transaction has no hash memo"),
+    };
+    if memo != proof_hash(&signed.message) {
+        return mismatch("the anchor transaction commits to another proof");
+    }
+
+    Anchor::Confirmed {
+        tx_hash: tx.hash.clone(),
+        ledger: tx.ledger,
+        closed_at: tx.created_at.clone(),
+    }
+}
+
+/// Looks up the anchor transaction of `signed` on horizon, None if the proof is not anchored
+pub async fn verify(signed: &SignedMessage) -> Result<Option<Anchor>> {
+    let tx_hash = match &signed.anchor {
+        Some(tx_hash) => tx_hash,
+        None => return Ok(None),
+    };
+    let tx = stellar::fetch_transaction(tx_hash)
+        .await
+        .map_err(|err| err.context("Looking up the anchor transaction"))?;
+
+    Ok(Some(check(signed, &tx)))
+}
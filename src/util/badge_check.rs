@@ -1,97 +1,456 @@
 use crate::stellar::*;
 use crate::util::error::Error;
+use crate::util::proof_encoding::Predicate;
 use futures::stream::StreamExt;
-use log::debug;
+use itertools::Itertools;
+use log::{debug, warn};
+use std::collections::HashMap;
 #[derive(Debug, Default, PartialEq, Clone)]
 pub struct Badge {
     pub token: stellar_data::TOMLCurrency,
     pub tx_hash: Option<String>,
     pub owned: bool,
     pub date_accuired: Option<String>,
+    pub provenance: Option<Provenance>,
+    pub pending: bool,
+    pub claimable_balance_id: Option<String>,
+    pub held: bool,
+    pub sent_to: Option<String>,
+    /// The account the badge was looked up in
+    pub account: String,
+}
+
+/// How a held badge ended up in the account
+#[derive(Debug, PartialEq, Clone)]
+pub enum Provenance {
+    /// Paid out directly by the quest issuer
+    IssuerPayment,
+    /// Claimed from a claimable balance created by the quest issuer
+    IssuerClaimableBalance,
+    /// Received from some account other than the issuer
+    Transferred { from: String },
+    /// Held by the account, but no matching incoming payment was found (DEX trade, account merge, ...)
+    Unexplained,
+}
+
+impl Provenance {
+    pub fn is_earned(&self) -> bool {
+        matches!(
+            self,
+            Provenance::IssuerPayment | Provenance::IssuerClaimableBalance
+        )
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Provenance::IssuerPayment => String::from("earned from the issuer"),
+            Provenance::IssuerClaimableBalance => {
+                String::from("earned from an issuer claimable balance")
+            }
+            Provenance::Transferred { from } => format!("transferred in from {}", from),
+            Provenance::Unexplained => String::from("held, origin unknown"),
+        }
+    }
+}
+
+/// Which property a badge has to fulfill to count towards a proof
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum OwnershipRequirement {
+    /// Received from the issuer at some point, may have been sent away since
+    #[default]
+    Earned,
+    /// Currently in the accounts balance, no matter where it came from
+    Held,
+    /// Received from the issuer and still in the accounts balance
+    EarnedAndHeld,
+}
+
+impl OwnershipRequirement {
+    pub fn is_met_by(&self, badge: &Badge) -> bool {
+        match self {
+            OwnershipRequirement::Earned => badge.is_earned(),
+            OwnershipRequirement::Held => badge.held,
+            OwnershipRequirement::EarnedAndHeld => badge.is_earned() && badge.held,
+        }
+    }
+
+    pub fn describe(&self) -> &'static str {
+        match self {
+            OwnershipRequirement::Earned => "earned",
+            OwnershipRequirement::Held => "currently held",
+            OwnershipRequirement::EarnedAndHeld => "earned and still held",
+        }
+    }
 }
 
 impl Badge {
     pub fn is_mono(&self) -> bool {
-        self.token.tag == String::from("mono")
+        self.token.tag == "mono"
+    }
+
+    pub fn is_earned(&self) -> bool {
+        self.owned && self.provenance.as_ref().is_some_and(|p| p.is_earned())
+    }
+
+    /// Orders badges of the same token, the higher the more requirements it meets
+    fn rank(&self) -> u8 {
+        match (self.is_earned(), self.held, self.owned, self.pending) {
+            (true, true, _, _) => 4,
+            (true, false, _, _) => 3,
+            (false, _, true, _) => 2,
+            (_, _, _, true) => 1,
+            _ => 0,
+        }
     }
 }
 
+/// Evaluates a proof predicate against the resolved badges of the proving accounts
+pub fn predicate_met(
+    predicate: &Predicate,
+    badges: &[Badge],
+    requirement: &OwnershipRequirement,
+) -> bool {
+    let met = |code: &String| {
+        badges
+            .iter()
+            .any(|b| &b.token.code == code && requirement.is_met_by(b))
+    };
+    match predicate {
+        Predicate::CompleteSeries(series) => {
+            let prefix = format!("SQ{:02}", series);
+            let series = badges
+                .iter()
+                .map(|b| &b.token.code)
+                .filter(|code| code.starts_with(&prefix) && code.len() == prefix.len() + 2)
+                .unique()
+                .collect::<Vec<&String>>();
+            !series.is_empty() && series.into_iter().all(met)
+        }
+        Predicate::AtLeast(count) => {
+            badges
+                .iter()
+                .map(|b| &b.token.code)
+                .unique()
+                .filter(|code| met(code))
+                .count()
+                >= *count
+        }
+    }
+}
+
+/// Merges the badges of several accounts, each resolved against the same available badges.
+/// The badges of every account are kept, per token the one meeting the most requirements
+/// comes first and the first account wins ties. A requirement has to be evaluated with `any`
+/// over all badges of a token: the best ranked one does not meet every requirement
+/// (earned by one account, held by another).
+pub fn merge_accounts(accounts: Vec<Vec<Badge>>) -> Vec<Badge> {
+    let mut accounts = accounts
+        .into_iter()
+        .map(|badges| badges.into_iter())
+        .collect::<Vec<_>>();
+    let mut merged = vec![];
+    loop {
+        let mut token = accounts
+            .iter_mut()
+            .filter_map(|badges| badges.next())
+            .collect::<Vec<Badge>>();
+        if token.is_empty() {
+            break;
+        }
+        token.sort_by_key(|b| std::cmp::Reverse(b.rank()));
+        merged.append(&mut token);
+    }
+    merged
+}
+
+/// Codes of the owned badges, color and mono variants counting once
+pub fn owned_codes(badges: &[Badge]) -> Vec<String> {
+    badges
+        .iter()
+        .filter(|b| b.owned)
+        .map(|b| b.token.code.clone())
+        .unique()
+        .collect()
+}
+
+/// Codes owned in any of `others` but not in `badges`, in the order of `others`
+pub fn missing_codes(badges: &[Badge], others: &[&[Badge]]) -> Vec<String> {
+    let owned = owned_codes(badges);
+    others
+        .iter()
+        .flat_map(|other| owned_codes(other))
+        .unique()
+        .filter(|code| !owned.contains(code))
+        .collect()
+}
+
+/// Resolves the badges of all `accounts` and merges them, see `merge_accounts`
+pub async fn fetch_badges_of_accounts(
+    accounts: &[String],
+    available_badges: &[stellar_data::TOMLCurrency],
+    options: &ResolveOptions,
+) -> Result<Vec<Badge>> {
+    let badges = futures::stream::iter(accounts.iter().map(|id| async move {
+        fetch_badges_with(id, available_badges, options)
+            .await
+            .map_err(|err| err.context(format!("The account {} could not be checked", id)))
+    }))
+    .buffered(options.concurrency.max(1))
+    .collect::<Vec<_>>()
+    .await
+    .into_iter()
+    .collect::<Result<Vec<_>>>()?;
+    Ok(merge_accounts(badges))
+}
+
 type Result<T> = std::result::Result<T, Error>;
 
+/// Amount of issuer scans running at the same time
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolveOptions {
+    /// Amount of issuer scans running at the same time
+    pub concurrency: usize,
+    /// Download the complete payment history instead of only the badge payments
+    pub full_scan: bool,
+    /// Assets (`CODE:ISSUER`) which have to be resolved even without a trustline, e.g. the
+    /// badges claimed by a proof. The complete history is downloaded if one of them is not trusted.
+    pub assets: Vec<String>,
+}
+
+impl Default for ResolveOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: DEFAULT_CONCURRENCY,
+            full_scan: false,
+            assets: vec![],
+        }
+    }
+}
+
 pub async fn fetch_badges(
-    id: &String,
-    available_badges: &Vec<stellar_data::TOMLCurrency>,
+    id: &str,
+    available_badges: &[stellar_data::TOMLCurrency],
+) -> Result<Vec<Badge>> {
+    fetch_badges_with(id, available_badges, &ResolveOptions::default()).await
+}
+
+pub async fn fetch_badges_with(
+    id: &str,
+    available_badges: &[stellar_data::TOMLCurrency],
+    options: &ResolveOptions,
 ) -> Result<Vec<Badge>> {
-    let payments = stellar::fetch_account_payments(id).await?;
+    let concurrency = options.concurrency;
     let balances = stellar::fetch_account(id).await?.balances;
 
+    // the payments of badges with a trustline bound the history to read
+    let trusted_assets = available_badges
+        .iter()
+        .filter(|t| {
+            balances.iter().any(|bal| {
+                &bal.asset_type == "credit_alphanum12"
+                    && bal.asset_code == t.code
+                    && bal.asset_issuer == t.issuer
+            })
+        })
+        .map(|t| t.asset())
+        .unique()
+        .collect::<Vec<String>>();
+
+    // a badge whose trustline was removed is only found in the history back to its
+    // removal, which nothing bounds without a trustline to stop at or a requested asset
+    let untrusted = options
+        .assets
+        .iter()
+        .filter(|a| !trusted_assets.contains(a))
+        .collect::<Vec<&String>>();
+    let full_scan = options.full_scan || trusted_assets.is_empty() || !untrusted.is_empty();
+    let payments = async {
+        if full_scan {
+            debug!(
+                "{}: reading the complete history, untrusted {:?}",
+                id, untrusted
+            );
+            stellar::fetch_account_payments(id).await
+        } else {
+            // trustlines of badges removed since are picked up on the way
+            let watched = available_badges
+                .iter()
+                .map(|t| t.asset())
+                .unique()
+                .collect::<Vec<String>>();
+            stellar::fetch_account_payments_for_assets(id, &trusted_assets, &watched).await
+        }
+    };
+    let (payments, claimable_balances) =
+        futures::join!(payments, stellar::fetch_account_claimable_balances(id));
+    let payments = payments?;
+    // pending badges are a bonus, the owned ones can be shown without them
+    let claimable_balances = claimable_balances.unwrap_or_else(|err| {
+        warn!(
+            "{}: could not load claimable balances: {}",
+            id,
+            err.report()
+        );
+        vec![]
+    });
+
     let badges = available_badges
-        .into_iter()
+        .iter()
         .map(|badge| {
             let payment = payments
-                .clone()
-                .into_iter()
-                .filter(|p| {
+                .iter()
+                .find(|p| {
                     p.asset_type == "credit_alphanum12"
                         && p.asset_issuer == p.from
+                        && p.to == id
                         && p.asset_issuer == badge.issuer
                         && p.asset_code == badge.code
                 })
-                .next();
+                .cloned();
             let mut badge = Badge {
                 token: badge.clone(),
                 tx_hash: None,
                 owned: false,
                 date_accuired: None,
+                provenance: None,
+                pending: false,
+                claimable_balance_id: None,
+                held: false,
+                sent_to: None,
+                account: id.to_string(),
             };
-            match payment {
-                Some(b) => {
-                    badge.tx_hash = Some(b.transaction_hash);
-                    badge.owned = true;
-                    badge.date_accuired = Some(b.created_at);
-                }
-                _ => {}
-            };
+            if let Some(b) = payment {
+                badge.tx_hash = Some(b.transaction_hash);
+                badge.owned = true;
+                badge.date_accuired = Some(b.created_at);
+                badge.provenance = Some(Provenance::IssuerPayment);
+            }
 
             badge
         })
         .collect::<Vec<Badge>>();
 
-    let badges = futures::stream::iter(badges)
-        .then(|mut b| async {
+    // badges held in a trustline but not paid out directly may have been claimed from the issuer
+    let unresolved = badges
+        .iter()
+        .filter(|b| !b.owned)
+        .filter(|b| {
+            balances.iter().any(|bal| {
+                &bal.asset_type == "credit_alphanum12"
+                    && bal.asset_code == b.token.code
+                    && bal.asset_issuer == b.token.issuer
+            })
+        })
+        .map(|b| (b.token.issuer.clone(), b.token.asset()))
+        .into_group_map();
+
+    // one scan per issuer serves all of its badges
+    let claimed_balances = futures::stream::iter(unresolved)
+        .map(|(issuer, assets)| async move {
+            debug!("{}: searching claimable balances for {:?}", issuer, assets);
+            stellar::search_created_claimed_balances(&issuer, &assets, id).await
+        })
+        .buffer_unordered(concurrency.max(1))
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .flatten()
+        .collect::<HashMap<_, _>>();
+
+    let badges = badges
+        .into_iter()
+        .map(|mut b| {
             if !b.owned {
-                let bal = balances
-                    .iter()
-                    .filter(|bal| {
-                        &bal.asset_type == "credit_alphanum12"
-                            && bal.asset_code == b.token.code
-                            && bal.asset_issuer == b.token.issuer
-                    })
-                    .next();
+                let bal = balances.iter().find(|bal| {
+                    &bal.asset_type == "credit_alphanum12"
+                        && bal.asset_code == b.token.code
+                        && bal.asset_issuer == b.token.issuer
+                });
                 if let Some(bal) = bal {
                     debug!("{}: not owned but in balance!", b.token.code);
-                    let asset = format!("{}:{}", bal.asset_code, bal.asset_issuer);
-                    let claimable_balance =
-                        stellar::search_created_claimed_balances(&bal.asset_issuer, &asset, id)
-                            .await;
+                    let claimable_balance = claimed_balances.get(&b.token.asset());
                     debug!(
                         "{}: found matching claimable balance? {}",
                         b.token.code,
                         claimable_balance.is_some()
                     );
-                    if claimable_balance.is_some() {
-                        let claimable_balance = claimable_balance.unwrap();
+                    if let Some(claimable_balance) = claimable_balance {
                         b.owned = true;
-                        b.tx_hash = Some(claimable_balance.transaction_hash);
-                        b.date_accuired = Some(claimable_balance.created_at)
+                        b.tx_hash = Some(claimable_balance.transaction_hash.clone());
+                        b.date_accuired = Some(claimable_balance.created_at.clone());
+                        b.provenance = Some(Provenance::IssuerClaimableBalance);
+                    } else if bal.balance.parse::<f64>().unwrap_or(0.0) > 0.0 {
+                        // not earned from the issuer, but currently held
+                        let transfer = payments.iter().find(|p| {
+                            p.asset_type == "credit_alphanum12"
+                                && p.to == id
+                                && p.from != p.asset_issuer
+                                && p.asset_issuer == b.token.issuer
+                                && p.asset_code == b.token.code
+                        });
+                        debug!(
+                            "{}: found matching transfer? {}",
+                            b.token.code,
+                            transfer.is_some()
+                        );
+                        b.owned = true;
+                        match transfer {
+                            Some(p) => {
+                                b.tx_hash = Some(p.transaction_hash.clone());
+                                b.date_accuired = Some(p.created_at.clone());
+                                b.provenance = Some(Provenance::Transferred {
+                                    from: p.from.clone(),
+                                });
+                            }
+                            None => b.provenance = Some(Provenance::Unexplained),
+                        }
                     }
                 }
             }
 
             b
         })
-        .collect::<Vec<_>>()
-        .await;
+        .collect::<Vec<Badge>>();
+
+    let badges = badges
+        .into_iter()
+        .map(|mut b| {
+            b.held = balances.iter().any(|bal| {
+                &bal.asset_type == "credit_alphanum12"
+                    && bal.asset_code == b.token.code
+                    && bal.asset_issuer == b.token.issuer
+                    && bal.balance.parse::<f64>().unwrap_or(0.0) > 0.0
+            });
+            if b.owned && !b.held {
+                // earned at some point, but no longer in the balance
+                b.sent_to = payments
+                    .iter()
+                    .rfind(|p| {
+                        p.asset_type == "credit_alphanum12"
+                            && p.from == id
+                            && p.to != id
+                            && p.asset_issuer == b.token.issuer
+                            && p.asset_code == b.token.code
+                    })
+                    .map(|p| p.to.clone());
+                debug!("{}: earned but sent away to {:?}", b.token.code, b.sent_to);
+            }
+            if !b.owned {
+                let asset = b.token.asset();
+                let open_balance = claimable_balances
+                    .iter()
+                    .find(|cb| cb.asset == asset && cb.claimable_by(id));
+                if let Some(cb) = open_balance {
+                    debug!("{}: pending in claimable balance {}", b.token.code, cb.id);
+                    b.pending = true;
+                    b.claimable_balance_id = Some(cb.id.clone());
+                }
+            }
+            b
+        })
+        .collect::<Vec<Badge>>();
 
     Ok(badges)
 }
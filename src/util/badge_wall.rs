@@ -0,0 +1,87 @@
+//! A single SVG image of the badges owned by an account, for sharing the collection.
+//!
+//! The badge images are referenced by url unless `images` holds an embedded version of them,
+//! only embedded images show up where the SVG is viewed as an image (`img` tags, READMEs).
+use crate::util::badge_check::Badge;
+use crate::util::svg;
+use itertools::Itertools;
+use std::collections::HashMap;
+
+pub const COLUMNS: usize = 8;
+const CELL: usize = 88;
+const IMAGE: usize = 64;
+const HEADER: usize = 72;
+const PADDING: usize = 16;
+
+/// The owned badges shown on the wall, the color variant if both are owned, by code
+pub fn wall_badges(badges: &[Badge]) -> Vec<&Badge> {
+    badges
+        .iter()
+        .filter(|b| b.owned)
+        .sorted_by(|a, b| {
+            a.token
+                .code
+                .cmp(&b.token.code)
+                .then_with(|| a.is_mono().cmp(&b.is_mono()))
+        })
+        .unique_by(|b| &b.token.code)
+        .collect()
+}
+
+/// Renders the wall of `account`, `images` maps image urls to the href to use instead
+pub fn render(account: &str, badges: &[Badge], images: &HashMap<String, String>) -> String {
+    let wall = wall_badges(badges);
+    let total = badges.iter().map(|b| &b.token.code).unique().count();
+    let rows = wall.len().div_ceil(COLUMNS);
+    let width = COLUMNS * CELL + 2 * PADDING;
+    let height = HEADER + rows.max(1) * CELL + PADDING;
+
+    let mut out = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif"><rect width="{w}" height="{h}" rx="12" fill="#ffffff"/>"##,
+        w = width,
+        h = height
+    );
+    out.push_str(&format!(
+        r##"<text x="{p}" y="32" font-size="20" font-weight="bold" fill="#363636">{} / {} Stellar Quest badges</text>"##,
+        wall.len(),
+        total,
+        p = PADDING
+    ));
+    out.push_str(&format!(
+        r##"<text x="{p}" y="54" font-size="11" font-family="monospace" fill="#7a7a7a">{}</text>"##,
+        svg::escape(account),
+        p = PADDING
+    ));
+
+    for (i, badge) in wall.iter().enumerate() {
+        let x = PADDING + (i % COLUMNS) * CELL + (CELL - IMAGE) / 2;
+        let y = HEADER + (i / COLUMNS) * CELL;
+        let href = images.get(&badge.token.image).unwrap_or(&badge.token.image);
+        out.push_str(&format!(
+            r##"<image x="{}" y="{}" width="{i}" height="{i}" href="{h}" xlink:href="{h}"/>"##,
+            x,
+            y,
+            i = IMAGE,
+            h = svg::escape(href)
+        ));
+        out.push_str(&format!(
+            r##"<text x="{}" y="{}" font-size="11" text-anchor="middle" fill="#4a4a4a">{}</text>"##,
+            x + IMAGE / 2,
+            y + IMAGE + 14,
+            svg::escape(&badge.token.code)
+        ));
+    }
+
+    out.push_str("</svg>");
+    out
+}
+
+/// Mime type of a badge image, by the extension of its url
+pub fn image_mime(url: &str) -> &'static str {
+    match url.rsplit('.').next().map(|ext| ext.to_ascii_lowercase()) {
+        Some(ext) if ext == "svg" => "image/svg+xml",
+        Some(ext) if ext == "jpg" || ext == "jpeg" => "image/jpeg",
+        Some(ext) if ext == "gif" => "image/gif",
+        _ => "image/png",
+    }
+}
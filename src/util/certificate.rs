@@ -0,0 +1,204 @@
+//! Printable certificate of a verified proof: an A4 landscape SVG listing the proven badges
+//! with their acquisition dates, and a link and QR code back to the proof page to verify it.
+use crate::util::badge_check::Badge;
+use crate::util::series::Series;
+use crate::util::svg;
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use qrcode::{Color, QrCode};
+
+/// A4 landscape at 96 dpi
+pub const WIDTH: usize = 1123;
+pub const HEIGHT: usize = 794;
+const MARGIN: usize = 80;
+const QR_SIZE: usize = 180;
+const LIST_TOP: usize = 290;
+const ROW_HEIGHT: usize = 22;
+const ROWS: usize = 17;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Certificate {
+    /// The proving account first, then the cosigners
+    pub accounts: Vec<String>,
+    /// Resolved badges that meet the proof, in any order and variant
+    pub badges: Vec<Badge>,
+    /// Series completed by the proven badges
+    pub series: Vec<Series>,
+    /// Url of the proof page
+    pub verify_url: String,
+    /// Hash of the confirmed anchor transaction
+    pub anchor: Option<String>,
+    pub issued_at: DateTime<Utc>,
+}
+
+impl Certificate {
+    /// Proven badge codes with the date they were first acquired, by code
+    pub fn entries(&self) -> Vec<(String, Option<String>)> {
+        self.badges
+            .iter()
+            .into_group_map_by(|b| &b.token.code)
+            .into_iter()
+            .map(|(code, badges)| {
+                let date = badges.iter().filter_map(|b| b.date_accuired.clone()).min();
+                (code.clone(), date)
+            })
+            .sorted()
+            .collect()
+    }
+
+    pub fn render(&self) -> String {
+        let entries = self.entries();
+        let (accounts, hold) = match self.accounts.len() > 1 {
+            true => ("accounts", "hold"),
+            false => ("account", "holds"),
+        };
+        let mut out = format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="serif" fill="#363636"><rect width="{w}" height="{h}" fill="#ffffff"/><rect x="24" y="24" width="{}" height="{}" fill="none" stroke="#b08d57" stroke-width="4"/><rect x="34" y="34" width="{}" height="{}" fill="none" stroke="#b08d57" stroke-width="1"/>"##,
+            WIDTH - 48,
+            HEIGHT - 48,
+            WIDTH - 68,
+            HEIGHT - 68,
+            w = WIDTH,
+            h = HEIGHT
+        );
+        out.push_str(&text(
+            WIDTH / 2,
+            120,
+            r#"font-size="42" text-anchor="middle" letter-spacing="2""#,
+            "Certificate of Completion",
+        ));
+        out.push_str(&text(
+            WIDTH / 2,
+            170,
+            r#"font-size="18" text-anchor="middle""#,
+            &format!(
+                "This certifies that the Stellar {} below {} the following {} Stellar Quest badges",
+                accounts,
+                hold,
+                entries.len()
+            ),
+        ));
+        for (i, account) in self.accounts.iter().enumerate() {
+            out.push_str(&text(
+                WIDTH / 2,
+                205 + i * 20,
+                r#"font-size="15" font-family="monospace" text-anchor="middle""#,
+                account,
+            ));
+        }
+        if !self.series.is_empty() {
+            out.push_str(&text(
+                WIDTH / 2,
+                LIST_TOP - 30,
+                r#"font-size="16" font-style="italic" text-anchor="middle""#,
+                &format!(
+                    "Completed series {}",
+                    self.series.iter().map(|s| s.number).join(", ")
+                ),
+            ));
+        }
+
+        let columns = entries.len().div_ceil(ROWS).max(1);
+        let column_width = (WIDTH - 2 * MARGIN - QR_SIZE - 40) / columns;
+        for (i, (code, date)) in entries.iter().enumerate() {
+            let x = MARGIN + (i / ROWS) * column_width;
+            let y = LIST_TOP + (i % ROWS) * ROW_HEIGHT;
+            out.push_str(&text(x, y, r#"font-size="15" font-weight="bold""#, code));
+            out.push_str(&text(
+                x + 80,
+                y,
+                r##"font-size="14" fill="#4a4a4a""##,
+                &date
+                    .as_ref()
+                    .map(|d| d.chars().take(10).collect())
+                    .unwrap_or(String::from("date unknown")),
+            ));
+        }
+
+        let footer = HEIGHT - MARGIN;
+        out.push_str(&text(
+            MARGIN,
+            footer - 40,
+            r#"font-size="14""#,
+            &format!("Issued {}", self.issued_at.format("%Y-%m-%d %H:%M UTC")),
+        ));
+        if let Some(tx_hash) = &self.anchor {
+            out.push_str(&text(
+                MARGIN,
+                footer - 20,
+                r#"font-size="11" font-family="monospace""#,
+                &format!("Anchored in transaction {}", tx_hash),
+            ));
+        }
+        out.push_str(&format!(
+            r##"<a href="{}"><text x="{}" y="{}" font-size="11" fill="#3273dc">Verify at {}</text></a>"##,
+            svg::escape(&self.verify_url),
+            MARGIN,
+            footer,
+            svg::escape(&shorten(&self.verify_url, 90))
+        ));
+        if let Some(qr) = qr_code(
+            &self.verify_url,
+            WIDTH - MARGIN - QR_SIZE,
+            HEIGHT - MARGIN - QR_SIZE,
+            QR_SIZE,
+        ) {
+            out.push_str(&qr);
+        }
+
+        out.push_str("</svg>");
+        out
+    }
+}
+
+fn text(x: usize, y: usize, attributes: &str, content: &str) -> String {
+    format!(
+        r#"<text x="{}" y="{}" {}>{}</text>"#,
+        x,
+        y,
+        attributes,
+        svg::escape(content)
+    )
+}
+
+/// `text` cut to `max` characters with an ellipsis, for urls holding the whole proof
+fn shorten(text: &str, max: usize) -> String {
+    match text.chars().count() > max {
+        true => format!("{}…", text.chars().take(max - 1).collect::<String>()),
+        false => String::from(text),
+    }
+}
+
+/// The QR code of `data` as a `size` square at `x`, `y`, None if `data` is too long for one
+pub fn qr_code(data: &str, x: usize, y: usize, size: usize) -> Option<String> {
+    let code = QrCode::new(data.as_bytes()).ok()?;
+    let width = code.width();
+    // one module of quiet zone on each side, white is the certificate background
+    let module = size as f64 / (width + 2) as f64;
+    let mut out = format!(
+        r##"<g transform="translate({} {}) scale({:.4})" fill="#000000" shape-rendering="crispEdges">"##,
+        x, y, module
+    );
+    for (row, colors) in code.to_colors().chunks(width).enumerate() {
+        // one rect per run of dark modules keeps the image small
+        let mut col = 0;
+        while col < width {
+            if colors[col] != Color::Dark {
+                col += 1;
+                continue;
+            }
+            let start = col;
+            while col < width && colors[col] == Color::Dark {
+                col += 1;
+            }
+            out.push_str(&format!(
+                r#"<rect x="{}" y="{}" width="{}" height="1"/>"#,
+                start + 1,
+                row + 1,
+                col - start
+            ));
+        }
+    }
+    out.push_str("</g>");
+    Some(out)
+}
@@ -0,0 +1,199 @@
+//! Options of the embeddable badge widget at `/embed/:id`, read from the query string,
+//! and the snippets other sites paste to show it.
+//!
+//! `?size=small|medium|large&series=1,2&theme=light|dark&missing=false`, unknown or invalid
+//! values fall back to the defaults so a typo never breaks the host page.
+use crate::util::badge_check::Badge;
+use crate::util::proof_encoding;
+use itertools::Itertools;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Size {
+    Small,
+    Medium,
+    Large,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmbedOptions {
+    pub size: Size,
+    pub theme: Theme,
+    /// Only show the badges of these series, all when empty
+    pub series: Vec<usize>,
+    /// Also show the badges not acquired yet, greyed out
+    pub missing: bool,
+}
+
+impl Default for EmbedOptions {
+    fn default() -> Self {
+        EmbedOptions {
+            size: Size::Medium,
+            theme: Theme::Light,
+            series: vec![],
+            missing: true,
+        }
+    }
+}
+
+impl Size {
+    fn parse(value: &str) -> Option<Size> {
+        match value {
+            "small" => Some(Size::Small),
+            "medium" => Some(Size::Medium),
+            "large" => Some(Size::Large),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Size::Small => "small",
+            Size::Medium => "medium",
+            Size::Large => "large",
+        }
+    }
+
+    /// Edge length of a badge image in pixels
+    pub fn pixels(&self) -> usize {
+        match self {
+            Size::Small => 40,
+            Size::Medium => 64,
+            Size::Large => 96,
+        }
+    }
+}
+
+impl Theme {
+    fn parse(value: &str) -> Option<Theme> {
+        match value {
+            "light" => Some(Theme::Light),
+            "dark" => Some(Theme::Dark),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+        }
+    }
+}
+
+impl EmbedOptions {
+    /// Reads the options from a query string, with or without the leading `?`
+    pub fn parse(query: &str) -> EmbedOptions {
+        let mut options = EmbedOptions::default();
+        for (key, value) in query
+            .trim_start_matches('?')
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+        {
+            let value = urldecode::decode(String::from(value));
+            match key {
+                "size" => options.size = Size::parse(&value).unwrap_or(options.size),
+                "theme" => options.theme = Theme::parse(&value).unwrap_or(options.theme),
+                "series" => {
+                    options.series = value
+                        .split(',')
+                        .filter_map(|n| n.trim().parse().ok())
+                        .sorted()
+                        .dedup()
+                        .collect()
+                }
+                "missing" => options.missing = value != "false",
+                _ => {}
+            }
+        }
+        options
+    }
+
+    /// The query string of the options, without the defaults, `?` included unless empty
+    pub fn query(&self) -> String {
+        let defaults = EmbedOptions::default();
+        let mut params = vec![];
+        if self.size != defaults.size {
+            params.push(format!("size={}", self.size.name()));
+        }
+        if !self.series.is_empty() {
+            params.push(format!("series={}", self.series.iter().join(",")));
+        }
+        if self.theme != defaults.theme {
+            params.push(format!("theme={}", self.theme.name()));
+        }
+        if !self.missing {
+            params.push(String::from("missing=false"));
+        }
+        match params.is_empty() {
+            true => String::default(),
+            false => format!("?{}", params.join("&")),
+        }
+    }
+
+    /// Whether the widget shows `badge`
+    pub fn shows(&self, badge: &Badge) -> bool {
+        if !self.missing && !badge.owned {
+            return false;
+        }
+        self.series.is_empty()
+            || proof_encoding::quest_position(&badge.token.code)
+                .map(|(series, _)| self.series.contains(&series))
+                .unwrap_or(false)
+    }
+}
+
+/// Url of the app page at `path` (a route path, `/` included) on the app served at `base`,
+/// which may have a path of its own, e.g. `https://user.github.io/sqbadge`
+pub fn app_url(base: &str, path: &str) -> String {
+    format!(
+        "{}/{}",
+        base.trim_end_matches('/'),
+        path.trim_start_matches('/')
+    )
+}
+
+/// Url of the widget of `account` on the app served at `base`
+pub fn embed_url(base: &str, account: &str, options: &EmbedOptions) -> String {
+    format!(
+        "{}{}",
+        app_url(base, &format!("/embed/{}", account)),
+        options.query()
+    )
+}
+
+/// An `iframe` showing the widget, sized for a row of badges
+pub fn iframe_snippet(base: &str, account: &str, options: &EmbedOptions) -> String {
+    format!(
+        r#"<iframe src="{}" width="100%" height="{}" style="border: 0" title="Stellar Quest badges" loading="lazy"></iframe>"#,
+        embed_url(base, account, options),
+        options.size.pixels() * 3
+    )
+}
+
+/// The `<sqbadge-widget>` web component of `sqbadge-widget.js`, which builds the iframe
+pub fn component_snippet(base: &str, account: &str, options: &EmbedOptions) -> String {
+    let mut attributes = vec![format!(r#"account="{}""#, account)];
+    if options.size != Size::Medium {
+        attributes.push(format!(r#"size="{}""#, options.size.name()));
+    }
+    if !options.series.is_empty() {
+        attributes.push(format!(r#"series="{}""#, options.series.iter().join(",")));
+    }
+    if options.theme != Theme::Light {
+        attributes.push(format!(r#"theme="{}""#, options.theme.name()));
+    }
+    if !options.missing {
+        attributes.push(String::from(r#"missing="false""#));
+    }
+    format!(
+        "<script src=\"{}\" defer></script>\n<sqbadge-widget {}></sqbadge-widget>",
+        app_url(base, "/sqbadge-widget.js"),
+        attributes.join(" ")
+    )
+}
@@ -1,9 +1,21 @@
+use std::fmt;
+
+/// Errors of the stellar and proof layers.
+///
+/// `report` renders the message shown to users, `code` a stable identifier for logs and tests.
+/// `Display` of a context only prints the context, its cause is the `source`.
 #[derive(Debug, PartialEq, Clone)]
 #[allow(dead_code)]
 pub enum Error {
     StellarErr(StellarErr),
     Other(String),
     ProofErr(ProofErr),
+    WalletErr(WalletErr),
+    /// `source` occured while `context`
+    Context {
+        context: String,
+        source: Box<Error>,
+    },
     Unknown,
 }
 
@@ -17,5 +29,169 @@ pub enum ProofErr {
 pub enum StellarErr {
     InvalidPublicKey,
     AccountNotFound,
+    NetworkError,
+    InvalidResponse,
+    TransactionFailed,
     Unknown,
 }
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum WalletErr {
+    NotInstalled,
+    Rejected(String),
+    InvalidResponse,
+}
+
+impl Error {
+    /// Wraps the error, describing what was being done when it occured
+    pub fn context<S: Into<String>>(self, context: S) -> Error {
+        Error::Context {
+            context: context.into(),
+            source: Box::new(self),
+        }
+    }
+
+    /// The context followed by all of its causes, the message shown to users,
+    /// e.g. `Checking the proof: Loading GABC: The account could not be found ...`
+    pub fn report(&self) -> String {
+        match self {
+            Error::Context { context, source } => format!("{}: {}", context, source.report()),
+            err => err.to_string(),
+        }
+    }
+
+    /// The innermost error, skipping all context
+    pub fn root(&self) -> &Error {
+        match self {
+            Error::Context { source, .. } => source.root(),
+            err => err,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self.root() {
+            Error::StellarErr(err) => err.code(),
+            Error::ProofErr(err) => err.code(),
+            Error::WalletErr(err) => err.code(),
+            Error::Other(_) => "other",
+            _ => "unknown",
+        }
+    }
+}
+
+impl StellarErr {
+    pub fn code(&self) -> &'static str {
+        match self {
+            StellarErr::InvalidPublicKey => "stellar.invalid_public_key",
+            StellarErr::AccountNotFound => "stellar.account_not_found",
+            StellarErr::NetworkError => "stellar.network_error",
+            StellarErr::InvalidResponse => "stellar.invalid_response",
+            StellarErr::TransactionFailed => "stellar.transaction_failed",
+            StellarErr::Unknown => "stellar.unknown",
+        }
+    }
+}
+
+impl ProofErr {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ProofErr::ProofInvalidEncoding => "proof.invalid_encoding",
+            ProofErr::ProofWrongVersion => "proof.wrong_version",
+        }
+    }
+}
+
+impl WalletErr {
+    pub fn code(&self) -> &'static str {
+        match self {
+            WalletErr::NotInstalled => "wallet.not_installed",
+            WalletErr::Rejected(_) => "wallet.rejected",
+            WalletErr::InvalidResponse => "wallet.invalid_response",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::StellarErr(err) => err.fmt(f),
+            Error::ProofErr(err) => err.fmt(f),
+            Error::WalletErr(err) => err.fmt(f),
+            Error::Other(message) => f.write_str(message),
+            Error::Context { context, .. } => f.write_str(context),
+            Error::Unknown => f.write_str("An unknown error occured!"),
+        }
+    }
+}
+
+impl fmt::Display for StellarErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            StellarErr::InvalidPublicKey => "The public key is not in a valid ed25519 format!",
+            StellarErr::AccountNotFound => "The account could not be found on the stellar network!",
+            StellarErr::NetworkError => "The stellar network could not be reached!",
+            StellarErr::InvalidResponse => {
+                "The stellar network sent a response that could not be read!"
+            }
+            StellarErr::TransactionFailed => "The transaction was rejected by the stellar network!",
+            StellarErr::Unknown => "Unknown error while trying to connect to the stellar network!",
+        })
+    }
+}
+
+impl fmt::Display for ProofErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ProofErr::ProofInvalidEncoding => "The proof could not be decoded!",
+            ProofErr::ProofWrongVersion => "The proof was created with an unsupported version!",
+        })
+    }
+}
+
+impl fmt::Display for WalletErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WalletErr::NotInstalled => f.write_str("The selected wallet is not installed!"),
+            WalletErr::Rejected(message) => {
+                write!(f, "The wallet rejected the request: {}", message)
+            }
+            WalletErr::InvalidResponse => {
+                f.write_str("The wallet sent a response that could not be read!")
+            }
+        }
+    }
+}
+
+/// `Display` of a context only prints the context, the cause is its `source`
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Context { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl std::error::Error for StellarErr {}
+
+impl std::error::Error for ProofErr {}
+
+impl std::error::Error for WalletErr {}
+
+impl From<StellarErr> for Error {
+    fn from(err: StellarErr) -> Self {
+        Error::StellarErr(err)
+    }
+}
+
+impl From<ProofErr> for Error {
+    fn from(err: ProofErr) -> Self {
+        Error::ProofErr(err)
+    }
+}
+
+impl From<WalletErr> for Error {
+    fn from(err: WalletErr) -> Self {
+        Error::WalletErr(err)
+    }
+}
@@ -0,0 +1,135 @@
+//! In memory signer for development and automated tests, only built with the `dev-signer` feature.
+//!
+//! The secret seed never leaves the memory of the current page.
+use crate::stellar::transaction::PUBLIC_NETWORK_PASSPHRASE;
+use crate::stellar::{stellar, strkey, transaction};
+use crate::util::error::{Error, WalletErr};
+use crate::util::proof_encoding::{SignatureScheme, PUBLIC_NETWORK};
+use crate::util::wallet::{SignedMessage, WalletKind, WalletProvider};
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer};
+use futures::future::{FutureExt, LocalBoxFuture};
+use log::debug;
+use sha2::{Digest, Sha256};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+type Result<T> = std::result::Result<T, Error>;
+
+thread_local! {
+    static UNLOCKED: RefCell<Option<Rc<LocalSigner>>> = const { RefCell::new(None) };
+}
+
+pub struct LocalSigner {
+    keypair: Keypair,
+    account: String,
+}
+
+impl LocalSigner {
+    /// Derives the key pair from an `S...` secret seed
+    pub fn from_secret(seed: &str) -> Result<Self> {
+        let seed = strkey::decode(strkey::VERSION_SEED, seed.trim())
+            .ok_or(Error::Other(String::from("Invalid secret seed!")))?;
+        let secret = SecretKey::from_bytes(&seed)
+            .map_err(|_| Error::Other(String::from("Invalid secret seed!")))?;
+        let public = PublicKey::from(&secret);
+        let account = strkey::encode(strkey::VERSION_ACCOUNT_ID, public.as_bytes());
+
+        Ok(Self {
+            keypair: Keypair { secret, public },
+            account,
+        })
+    }
+
+    pub fn account(&self) -> &String {
+        &self.account
+    }
+
+    /// Signs `pubkey:message` the same way albedos `signMessage` does
+    pub fn sign_message(&self, message: &str) -> SignedMessage {
+        self.sign_message_with(SignatureScheme::Albedo, message)
+    }
+
+    pub fn sign_message_with(&self, scheme: SignatureScheme, message: &str) -> SignedMessage {
+        let signature = self.keypair.sign(&scheme.payload(&self.account, message));
+
+        SignedMessage {
+            scheme,
+            network: String::from(PUBLIC_NETWORK),
+            pubkey: self.account.clone(),
+            message: String::from(message),
+            signature: hex::encode(signature.to_bytes()),
+            anchor: None,
+            cosigners: vec![],
+        }
+    }
+
+    /// Signs an unsigned transaction envelope for the network identified by `passphrase`
+    pub fn sign_transaction(&self, envelope: &str, passphrase: &str) -> Result<String> {
+        let mut network_id = [0u8; 32];
+        network_id.copy_from_slice(&Sha256::digest(passphrase.as_bytes()));
+
+        let base = transaction::signature_base(envelope, &network_id)?;
+        let signature = self.keypair.sign(&Sha256::digest(&base));
+
+        transaction::add_signature(
+            envelope,
+            self.keypair.public.as_bytes(),
+            &signature.to_bytes(),
+        )
+    }
+}
+
+/// Makes `signer` the key used by `WalletKind::Local`, replacing any previously unlocked one
+pub fn unlock(signer: LocalSigner) {
+    UNLOCKED.with(|u| *u.borrow_mut() = Some(Rc::new(signer)));
+}
+
+fn unlocked() -> Result<Rc<LocalSigner>> {
+    UNLOCKED
+        .with(|u| u.borrow().clone())
+        .ok_or(Error::WalletErr(WalletErr::NotInstalled))
+}
+
+fn unlocked_for(pub_key: &str) -> Result<Rc<LocalSigner>> {
+    let signer = unlocked()?;
+    if signer.account() != pub_key {
+        return Err(Error::WalletErr(WalletErr::Rejected(String::from(
+            "the unlocked key does not belong to this account",
+        ))));
+    }
+    Ok(signer)
+}
+
+/// Wallet provider signing with the unlocked `LocalSigner`
+pub struct Local;
+
+impl WalletProvider for Local {
+    fn kind(&self) -> WalletKind {
+        WalletKind::Local
+    }
+
+    fn public_key(&self) -> LocalBoxFuture<'static, Result<String>> {
+        let account = unlocked().map(|s| s.account().clone());
+        async move { account }.boxed_local()
+    }
+
+    fn sign_message(
+        &self,
+        pub_key: &str,
+        message: &str,
+    ) -> LocalBoxFuture<'static, Result<SignedMessage>> {
+        let signed = unlocked_for(pub_key).map(|s| s.sign_message(message));
+        async move { signed }.boxed_local()
+    }
+
+    fn sign_transaction(&self, pub_key: &str, xdr: &str) -> LocalBoxFuture<'static, Result<()>> {
+        let signed =
+            unlocked_for(pub_key).and_then(|s| s.sign_transaction(xdr, PUBLIC_NETWORK_PASSPHRASE));
+        async move {
+            let hash = stellar::submit_transaction(&signed?).await?;
+            debug!("submitted transaction {}", hash);
+            Ok(())
+        }
+        .boxed_local()
+    }
+}
@@ -1,3 +1,15 @@
+pub mod anchor;
 pub mod badge_check;
+pub mod badge_wall;
+pub mod certificate;
+pub mod embed;
 pub mod error;
+#[cfg(feature = "dev-signer")]
+pub mod local_signer;
 pub mod proof_encoding;
+pub mod revocation;
+pub mod roster;
+pub mod series;
+pub mod svg;
+pub mod timeline;
+pub mod wallet;
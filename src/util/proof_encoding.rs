@@ -1,10 +1,12 @@
 use crate::{
-    js::albedo,
+    stellar::strkey,
     util::error::{Error, StellarErr},
 };
+use ed25519_dalek::{PublicKey, Signature, Verifier};
 use itertools::Itertools;
-use js_sys::JsString;
-use log::{debug, info, warn};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 use crate::stellar::stellar_data::TOMLCurrency;
 
@@ -15,208 +17,573 @@ pub struct Proof {
     pub owned_badges: Vec<TOMLCurrency>,
     pub timestamp: Option<i64>,
     pub unique_id: Option<String>,
+    /// Claims checked against the chain, the badges behind them are not listed
+    pub predicates: Vec<Predicate>,
+}
+
+/// A claim about the badges of the proving accounts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Predicate {
+    /// Owns every available quest badge `SQxx01`, `SQxx02`, ... of the series
+    CompleteSeries(usize),
+    /// Owns at least this many distinct badges
+    AtLeast(usize),
+}
+
+impl Predicate {
+    pub fn encode(&self) -> String {
+        match self {
+            Predicate::CompleteSeries(series) => format!("all{:02}", series),
+            Predicate::AtLeast(count) => format!("min{}", count),
+        }
+    }
+
+    pub fn decode(encoded: &str) -> Option<Predicate> {
+        let digits = |s: &str| -> Option<usize> {
+            match !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) {
+                true => s.parse().ok(),
+                false => None,
+            }
+        };
+        if let Some(series) = encoded.strip_prefix("all") {
+            return digits(series)
+                .filter(|s| *s > 0)
+                .map(Predicate::CompleteSeries);
+        }
+        if let Some(count) = encoded.strip_prefix("min") {
+            return digits(count).map(Predicate::AtLeast);
+        }
+        None
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Predicate::CompleteSeries(series) => format!("owns all badges of series {}", series),
+            Predicate::AtLeast(count) => format!("owns at least {} badges", count),
+        }
+    }
+}
+
+/// Badges per series: the special `SSQxx` badge (quest 0) and quests 1 to 8
+const QUESTS_PER_SERIES: usize = 9;
+
+/// Position of a badge in the encoding as (series, quest), `SSQxx` badges being quest 0
+pub fn quest_position(code: &str) -> Option<(usize, usize)> {
+    let (series, quest) = if let Some(series) = code.strip_prefix("SSQ") {
+        (series, "00")
+    } else if let Some(rest) = code.strip_prefix("SQ") {
+        if rest.len() != 4 || !rest.is_char_boundary(2) {
+            return None;
+        }
+        rest.split_at(2)
+    } else {
+        return None;
+    };
+
+    if series.len() != 2
+        || !series
+            .bytes()
+            .chain(quest.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let series: usize = series.parse().ok()?;
+    let quest: usize = quest.parse().ok()?;
+    if series == 0 || quest >= QUESTS_PER_SERIES {
+        return None;
+    }
+    Some((series, quest))
+}
+
+/// Bitmap of the owned badges, hex encoded
+fn encode_badges(owned_badges: &[TOMLCurrency]) -> String {
+    let owned_badges = owned_badges
+        .iter()
+        .unique_by(|b| b.code.clone())
+        .filter_map(|b| {
+            let position = quest_position(&b.code);
+            if position.is_none() {
+                warn!("{} can not be encoded, skipping", b.code);
+            }
+            position
+        })
+        .collect::<Vec<(usize, usize)>>();
+
+    // each series (index from 0) has 9 bits of storage where the LSB is Q00 (SSQ) and MSB is Q08
+    // bit == 1 <=> user owns badge
+    // encoding may be prefixed with zeros (-> any new quests are automatically set to not be owned)
+    let series_num = owned_badges.iter().map(|b| b.0).max().unwrap_or(0);
+    let mut bits = vec![false; series_num * QUESTS_PER_SERIES];
+    for (series, quest) in owned_badges {
+        debug!(
+            "S{}Q{} -> bit {}",
+            series,
+            quest,
+            (series - 1) * QUESTS_PER_SERIES + quest
+        );
+        bits[(series - 1) * QUESTS_PER_SERIES + quest] = true;
+    }
+
+    // hex encoded, most significant nibble first
+    let value = bits
+        .chunks(4)
+        .map(|nibble| {
+            nibble
+                .iter()
+                .enumerate()
+                .fold(0u8, |old, (index, bit)| old | ((*bit as u8) << index))
+        })
+        .rev()
+        .skip_while(|nibble| *nibble == 0)
+        .map(|nibble| format!("{:x}", nibble))
+        .collect::<String>();
+
+    if value.is_empty() {
+        String::from("0")
+    } else {
+        value
+    }
+}
+
+/// The available badges set in the bitmap written by `encode_badges`
+fn decode_badges(
+    badges: &str,
+    available_badges: &[TOMLCurrency],
+) -> Result<Vec<TOMLCurrency>, Error> {
+    // least significant nibble first
+    let mut bits = vec![];
+    for digit in badges.chars().rev() {
+        let nibble = digit
+            .to_digit(16)
+            .ok_or(Error::ProofErr(ProofErr::ProofInvalidEncoding))?;
+        bits.extend((0..4).map(|index| nibble & (1 << index) != 0));
+    }
+
+    let owned_badges_name = bits
+        .into_iter()
+        .enumerate()
+        .filter(|(_, owned)| *owned)
+        .map(|(index, _)| {
+            let q_series = index / QUESTS_PER_SERIES + 1;
+            let q_challenge = index % QUESTS_PER_SERIES;
+            let quest_name = match q_challenge {
+                0 => format!("SSQ{:02}", q_series),
+                _ => format!("SQ{:02}{:02}", q_series, q_challenge),
+            };
+            debug!("{} => true", quest_name);
+            quest_name
+        })
+        .collect::<Vec<String>>();
+
+    //TODO: This will add mono as well as normal badges even if account only ownes one of the two...
+    Ok(available_badges
+        .iter()
+        .filter(|b| owned_badges_name.contains(&b.code))
+        .cloned()
+        .collect())
 }
 
 impl Proof {
+    /// Encodes as `v1`, or as `v2` if the proof contains predicates
+    pub fn encode(&self) -> Result<String, Error> {
+        match self.predicates.is_empty() {
+            true => self.encode_v1(),
+            false => self.encode_v2(),
+        }
+    }
+
+    /// Decodes any version written by `encode`
+    pub fn decode(encoded: &String, available_badges: &[TOMLCurrency]) -> Result<Proof, Error> {
+        match encoded.split('.').next() {
+            Some("v2") => Proof::decode_v2(encoded, available_badges),
+            _ => Proof::decode_v1(encoded, available_badges),
+        }
+    }
+
+    /// `v1.badges.timestamp.unique_id`, predicates are lost
     pub fn encode_v1(&self) -> Result<String, Error> {
-        let owned_badges = self
-            .owned_badges
-            .clone()
-            .into_iter()
-            .unique_by(|b| b.code.clone())
-            .map(|b| {
-                let name = b.code;
-                let mut iter = name.chars().fuse();
-                let mut series: u32;
-                let mut task: u32;
-                if name.starts_with("SSQ") {
-                    iter.nth(2); // skip 3
-                    task = 0; // special badges are 1 per series
-                    series = iter.next().unwrap().to_digit(10).unwrap() * 10;
-                    series += iter.next().unwrap().to_digit(10).unwrap();
-                    //   SSQ        xx
-                    //    v         v
-                    // \ void /  $series
-                } else {
-                    iter.nth(1); // skip 2
-
-                    series = iter.next().unwrap().to_digit(10).unwrap() * 10;
-                    series += iter.next().unwrap().to_digit(10).unwrap();
-                    task = iter.next().unwrap().to_digit(10).unwrap() * 10;
-                    task += iter.next().unwrap().to_digit(10).unwrap();
-                    //    SQ      xx     xx
-                    //    v        v      v
-                    // \ void / $series $task
-                }
-
-                (series as u8, task as u8)
-            });
-        let mut translated =
-            vec![0; owned_badges.clone().into_iter().map(|b| b.0).max().unwrap() as usize]; // initialize with amout of series entries
-
-        for b in owned_badges {
-            if b.1 > 8 {
-                continue; // only allow 8+1 badges per series
-            }
-            translated[b.0 as usize - 1] = translated[b.0 as usize - 1] + (1 << b.1);
-            debug!(
-                "S{}Q{} -> {}:{}",
-                b.0,
-                b.1,
-                b.0,
-                translated[b.0 as usize - 1]
-            );
-            // each series (index from 0) has one byte of storage where the LSB is Q00 (SSQ) and MSB is Q07
-            // bit == 1 <=> user owns badge
-            // encoding may be prefixed with zeros (-> any new quests are automatically set to not be owned)
-        }
-
-        debug!("translated values per quest: {:?}", translated);
-
-        let value = translated
-            .into_iter()
-            .enumerate()
-            .fold(0usize, |old, (index, val)| old + (val << 9 * index)); // 9 badges per series
+        Ok(format!(
+            "v1.{}.{}.{}",
+            encode_badges(&self.owned_badges),
+            self.timestamp.map(|t| t.to_string()).unwrap_or_default(),
+            self.unique_id.clone().unwrap_or_default()
+        ))
+    }
 
+    pub fn decode_v1(encoded: &String, available_badges: &[TOMLCurrency]) -> Result<Proof, Error> {
+        let parts = encoded.splitn(4, ".").collect::<Vec<&str>>();
+        debug!("decoding proof({}): {:?}", encoded, parts);
+        if parts.len() < 4 {
+            return Err(Error::ProofErr(ProofErr::ProofInvalidEncoding));
+        }
+        if parts[0] != "v1" {
+            return Err(Error::ProofErr(ProofErr::ProofWrongVersion));
+        }
+
+        Ok(Proof {
+            owned_badges: decode_badges(parts[1], available_badges)?,
+            timestamp: decode_timestamp(parts[2]),
+            unique_id: decode_unique_id(parts[3]),
+            predicates: vec![],
+        })
+    }
+
+    /// `v2.badges.timestamp.predicates.unique_id`, the predicates separated by commas
+    pub fn encode_v2(&self) -> Result<String, Error> {
         Ok(format!(
-            "v1.{:x}.{}.{}",
-            value,
-            self.timestamp.unwrap_or(0),
-            self.unique_id.clone().unwrap_or(String::default())
+            "v2.{}.{}.{}.{}",
+            encode_badges(&self.owned_badges),
+            self.timestamp.map(|t| t.to_string()).unwrap_or_default(),
+            self.predicates.iter().map(|p| p.encode()).join(","),
+            self.unique_id.clone().unwrap_or_default()
         ))
     }
 
-    pub fn decode_v1(
-        encoded: &String,
-        available_badges: &Vec<TOMLCurrency>,
-    ) -> Result<Proof, Error> {
-        let mut parts = encoded.split(".");
-        let parts_arr = parts.clone().collect::<Vec<&str>>();
-        debug!("decoding proof({}): {:?}", encoded, parts_arr);
-        if parts_arr.len() < 4 {
+    pub fn decode_v2(encoded: &String, available_badges: &[TOMLCurrency]) -> Result<Proof, Error> {
+        let parts = encoded.splitn(5, ".").collect::<Vec<&str>>();
+        debug!("decoding proof({}): {:?}", encoded, parts);
+        if parts.len() < 5 {
             return Err(Error::ProofErr(ProofErr::ProofInvalidEncoding));
         }
-        if parts.next().unwrap() != "v1" {
+        if parts[0] != "v2" {
             return Err(Error::ProofErr(ProofErr::ProofWrongVersion));
         }
-        let badges = parts.next().unwrap();
-        let datetime = parts.next().unwrap();
+        let predicates = parts[3]
+            .split(',')
+            .filter(|p| !p.is_empty())
+            .map(|p| Predicate::decode(p).ok_or(Error::ProofErr(ProofErr::ProofInvalidEncoding)))
+            .collect::<Result<Vec<Predicate>, Error>>()?;
+
+        Ok(Proof {
+            owned_badges: decode_badges(parts[1], available_badges)?,
+            timestamp: decode_timestamp(parts[2]),
+            unique_id: decode_unique_id(parts[4]),
+            predicates,
+        })
+    }
+}
 
-        let mut unique_id = String::new();
-        parts.for_each(|p| unique_id.push_str(p));
+fn decode_timestamp(timestamp: &str) -> Option<i64> {
+    match timestamp.is_empty() {
+        true => None,
+        false => timestamp.parse().ok(),
+    }
+}
 
-        let mut final_proof = Proof::default();
+fn decode_unique_id(unique_id: &str) -> Option<String> {
+    match unique_id.is_empty() {
+        true => None,
+        false => Some(String::from(unique_id)),
+    }
+}
 
-        final_proof.unique_id = match unique_id == "" {
-            true => None,
-            false => Some(String::from(unique_id)),
-        };
-        final_proof.timestamp = match datetime == "" {
-            true => None,
-            false => datetime.parse().ok(),
-        };
+/// How the message of a proof was signed
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureScheme {
+    /// Albedos `signMessage`: ed25519 over `pubkey:message`
+    #[default]
+    Albedo,
+    /// SEP-53: ed25519 over sha256(`Stellar Signed Message:\n` + message)
+    Sep53,
+}
 
-        let mut badges = u64::from_str_radix(badges, 16).unwrap_or(0);
+/// Prefix SEP-53 puts in front of a message before hashing and signing it
+pub const SEP53_PREFIX: &str = "Stellar Signed Message:\n";
 
-        let mut q_series = 1;
-        let mut q_challenge = 0;
-        let mut owned_badges_name: Vec<String> = vec![];
-        while badges != 0 {
-            let badge_completed = badges % 2 == 1;
-            let quest_name = match q_challenge {
-                0 => format!("SSQ{:02}", q_series),
-                _ => format!("SQ{:02}{:02}", q_series, q_challenge),
-            };
-            debug!("{} => {}", quest_name, badge_completed);
+impl SignatureScheme {
+    /// Tag in front of the signed message, albedo messages have none for compatibility
+    pub fn tag(&self) -> Option<&'static str> {
+        match self {
+            SignatureScheme::Albedo => None,
+            SignatureScheme::Sep53 => Some("sep53"),
+        }
+    }
 
-            if badge_completed {
-                owned_badges_name.push(quest_name);
-            }
+    pub fn describe(&self) -> &'static str {
+        match self {
+            SignatureScheme::Albedo => "Albedo",
+            SignatureScheme::Sep53 => "SEP-53",
+        }
+    }
 
-            badges = badges >> 1;
-            q_challenge += 1;
-            if q_challenge > 8 {
-                q_challenge = 0;
-                q_series += 1;
+    /// The bytes the ed25519 signature is computed over
+    pub fn payload(&self, pub_key: &str, message: &str) -> Vec<u8> {
+        match self {
+            SignatureScheme::Albedo => format!("{}:{}", pub_key, message).into_bytes(),
+            SignatureScheme::Sep53 => {
+                let mut hasher = Sha256::new();
+                hasher.update(SEP53_PREFIX.as_bytes());
+                hasher.update(message.as_bytes());
+                hasher.finalize().to_vec()
             }
         }
+    }
+}
+
+/// Version of the proof envelope written by `SignedMessage::encode`
+pub const ENVELOPE_VERSION: u32 = 1;
+/// The only network proofs are currently created for
+pub const PUBLIC_NETWORK: &str = "public";
+
+/// A signed proof message
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct SignedMessage {
+    pub scheme: SignatureScheme,
+    pub network: String,
+    pub pubkey: String,
+    /// the encoded `Proof`
+    pub message: String,
+    /// hex encoded ed25519 signature over `scheme.payload(pubkey, signed_text())`
+    pub signature: String,
+    /// hash of the transaction anchoring the proof on-chain, see `util::anchor`
+    pub anchor: Option<String>,
+    /// further accounts signing the same message, their badges count towards the proof.
+    /// All accounts sign `signed_text`, which binds the message to the set of accounts.
+    pub cosigners: Vec<Cosignature>,
+}
 
-        //TODO: This will add mono as well as normal badges even if account only ownes one of the two...
-        final_proof.owned_badges = available_badges
-            .clone()
-            .into_iter()
-            .filter(|b| owned_badges_name.contains(&b.code))
-            .collect();
+/// Signature of a further account over the message of a `SignedMessage`
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Cosignature {
+    pub scheme: SignatureScheme,
+    pub pubkey: String,
+    pub signature: String,
+}
 
-        Ok(final_proof)
+/// The text every account of a proof signs. Cosigned proofs append their sorted accounts,
+/// so a cosignature can not be moved into a proof with another set of accounts.
+pub fn signed_text(message: &str, accounts: &[String]) -> String {
+    match accounts.len() {
+        0 | 1 => String::from(message),
+        _ => format!(
+            "{}\naccounts:{}",
+            message,
+            accounts.iter().sorted().join(",")
+        ),
     }
 }
 
-pub fn verify_albedo_signed_message(
-    base64_proof: &String,
-) -> Result<(bool, String, String), Error> {
-    debug!("Trying to decrypt albedo signed message {}", base64_proof);
+impl Cosignature {
+    /// Checks the ed25519 signature over `message` in rust
+    pub fn verify(&self, message: &str) -> Result<bool, Error> {
+        verify_ed25519(self.scheme, &self.pubkey, message, &self.signature)
+    }
+}
 
-    let bytes = base64::decode(base64_proof);
+/// Serialized form of a `SignedMessage`, base64url encoded JSON
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope {
+    version: u32,
+    scheme: SignatureScheme,
+    network: String,
+    pubkey: String,
+    signature: String,
+    proof: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    anchor: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    cosigners: Vec<Cosignature>,
+}
 
-    if bytes.is_err() {
-        return Err(Error::ProofErr(ProofErr::ProofInvalidEncoding));
+impl SignedMessage {
+    /// Encodes the message as versioned envelope
+    pub fn encode(&self) -> String {
+        let envelope = Envelope {
+            version: ENVELOPE_VERSION,
+            scheme: self.scheme,
+            network: self.network.clone(),
+            pubkey: self.pubkey.clone(),
+            signature: self.signature.clone(),
+            proof: self.message.clone(),
+            anchor: self.anchor.clone(),
+            cosigners: self.cosigners.clone(),
+        };
+        let json = serde_json::to_string(&envelope).unwrap_or_default();
+        base64::encode_config(json, base64::URL_SAFE_NO_PAD)
     }
 
-    let proof = String::from_utf8(bytes.unwrap());
-
-    debug!("Decoded {:?}", proof);
+    /// Encodes the message as base64 `[scheme:]signature:pubkey:message` blob,
+    /// the format used before the envelope was introduced. Anchor and cosigners are lost.
+    pub fn encode_legacy(&self) -> String {
+        let mut blob = String::new();
+        if let Some(tag) = self.scheme.tag() {
+            blob.push_str(tag);
+            blob.push(':');
+        }
+        blob.push_str(&format!(
+            "{}:{}:{}",
+            self.signature, self.pubkey, self.message
+        ));
+        base64::encode(blob)
+    }
 
-    if proof.is_err() {
-        return Err(Error::ProofErr(ProofErr::ProofInvalidEncoding));
+    /// Decodes an envelope or a legacy blob
+    pub fn parse(encoded: &String) -> Result<SignedMessage, Error> {
+        match SignedMessage::parse_envelope(encoded) {
+            Some(signed) => signed,
+            None => SignedMessage::parse_legacy(encoded),
+        }
     }
 
-    let split = proof
-        .unwrap()
-        .split(":")
-        .map(|s| s.to_owned())
-        .collect::<Vec<String>>();
+    /// None if `encoded` is not an envelope at all
+    fn parse_envelope(encoded: &str) -> Option<Result<SignedMessage, Error>> {
+        let json = base64::decode_config(encoded.trim(), base64::URL_SAFE_NO_PAD).ok()?;
+        let value = serde_json::from_slice::<serde_json::Value>(&json).ok()?;
+        if value.get("version")?.as_u64()? != ENVELOPE_VERSION as u64 {
+            return Some(Err(Error::ProofErr(ProofErr::ProofWrongVersion)));
+        }
+        debug!("Decoded envelope {:?}", value);
 
-    if split.len() < 3 {
-        return Err(Error::ProofErr(ProofErr::ProofInvalidEncoding));
+        let envelope = match serde_json::from_value::<Envelope>(value) {
+            Ok(envelope) => envelope,
+            Err(_) => return Some(Err(Error::ProofErr(ProofErr::ProofInvalidEncoding))),
+        };
+        let signed = SignedMessage {
+            scheme: envelope.scheme,
+            network: envelope.network,
+            pubkey: envelope.pubkey,
+            message: envelope.proof,
+            signature: envelope.signature,
+            anchor: envelope.anchor,
+            cosigners: envelope.cosigners,
+        };
+        // every account may only count once
+        if signed.accounts().iter().unique().count() != signed.cosigners.len() + 1 {
+            return Some(Err(Error::ProofErr(ProofErr::ProofInvalidEncoding)));
+        }
+        Some(Ok(signed))
     }
-    let message_sig = split[0].clone();
-    let pub_key = split[1].clone();
-    let mut plain_message = String::new();
 
-    split.iter().skip(2).for_each(|s| plain_message.push_str(s));
+    /// Decodes a base64 encoded `[scheme:]signature:pubkey:message` blob
+    pub fn parse_legacy(base64_proof: &String) -> Result<SignedMessage, Error> {
+        debug!("Trying to decode signed message {}", base64_proof);
 
-    let valid = albedo::albedo_verify_message_signature(
-        JsString::from(pub_key.clone()),
-        JsString::from(plain_message.clone()),
-        JsString::from(message_sig.clone()),
-    );
+        let bytes = base64::decode(base64_proof)
+            .map_err(|_| Error::ProofErr(ProofErr::ProofInvalidEncoding))?;
+        let proof = String::from_utf8(bytes)
+            .map_err(|_| Error::ProofErr(ProofErr::ProofInvalidEncoding))?;
 
-    if valid.is_err() {
-        let err = valid.err().unwrap();
+        debug!("Decoded {:?}", proof);
 
-        debug!("albedo error while verifying: {:?}", err);
+        // an envelope which happens to be valid standard base64 as well
+        if proof.starts_with('{') {
+            return Err(Error::ProofErr(ProofErr::ProofInvalidEncoding));
+        }
 
-        let msg = format!("{:?}", err);
+        let (scheme, rest) = match proof.split_once(':') {
+            Some((tag, rest)) if Some(tag) == SignatureScheme::Sep53.tag() => {
+                (SignatureScheme::Sep53, rest)
+            }
+            _ => (SignatureScheme::Albedo, proof.as_str()),
+        };
 
-        if msg.starts_with("JsValue(Error: Invalid public key format.") {
-            return Err(Error::StellarErr(StellarErr::InvalidPublicKey));
+        // the message itself may contain colons
+        let split = rest.splitn(3, ':').collect::<Vec<&str>>();
+        if split.len() < 3 {
+            return Err(Error::ProofErr(ProofErr::ProofInvalidEncoding));
         }
 
-        return Err(Error::Unknown);
+        Ok(SignedMessage {
+            scheme,
+            network: String::from(PUBLIC_NETWORK),
+            signature: split[0].to_owned(),
+            pubkey: split[1].to_owned(),
+            message: split[2].to_owned(),
+            anchor: None,
+            cosigners: vec![],
+        })
+    }
+
+    /// The signing account followed by all cosigners
+    pub fn accounts(&self) -> Vec<String> {
+        std::iter::once(self.pubkey.clone())
+            .chain(self.cosigners.iter().map(|c| c.pubkey.clone()))
+            .collect()
+    }
+
+    /// The text the account and all cosigners signed, see `signed_text`
+    pub fn signed_text(&self) -> String {
+        signed_text(&self.message, &self.accounts())
+    }
+
+    /// Checks all ed25519 signatures in rust, independent of any wallet
+    pub fn verify(&self) -> Result<bool, Error> {
+        let text = self.signed_text();
+        if !verify_ed25519(self.scheme, &self.pubkey, &text, &self.signature)? {
+            return Ok(false);
+        }
+        for cosigner in &self.cosigners {
+            if !cosigner.verify(&text)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
     }
+}
+
+fn verify_ed25519(
+    scheme: SignatureScheme,
+    pubkey: &str,
+    message: &str,
+    signature: &str,
+) -> Result<bool, Error> {
+    let pub_key = strkey::decode(strkey::VERSION_ACCOUNT_ID, pubkey)
+        .and_then(|key| PublicKey::from_bytes(&key).ok())
+        .ok_or(Error::StellarErr(StellarErr::InvalidPublicKey))?;
+    let signature = match hex::decode(signature)
+        .ok()
+        .and_then(|sig| Signature::from_bytes(&sig).ok())
+    {
+        Some(signature) => signature,
+        None => return Ok(false),
+    };
+
+    Ok(pub_key
+        .verify(&scheme.payload(pubkey, message), &signature)
+        .is_ok())
+}
+
+/// Splits a base64 encoded `signature:pubkey:message` blob as returned by albedo
+/// into (signature, public key, plain message)
+pub fn parse_albedo_signed_message(
+    base64_proof: &String,
+) -> Result<(String, String, String), Error> {
+    let signed = SignedMessage::parse_legacy(base64_proof)?;
+    if signed.scheme != SignatureScheme::Albedo {
+        return Err(Error::ProofErr(ProofErr::ProofInvalidEncoding));
+    }
+
+    Ok((signed.signature, signed.pubkey, signed.message))
+}
+
+/// Legacy decoder for albedo `signature:pubkey:message` blobs, returns (valid, message, public key)
+pub fn verify_albedo_signed_message(
+    base64_proof: &String,
+) -> Result<(bool, String, String), Error> {
+    let signed = SignedMessage::parse_legacy(base64_proof)?;
+    let valid = signed.verify()?;
+
+    Ok((valid, signed.message, signed.pubkey))
+}
 
-    let valid = valid.unwrap().value_of();
+/// Verifies an envelope or legacy blob of any scheme with `SignedMessage::verify`,
+/// returns the validity and the signed message
+pub fn verify_signed_message(encoded: &String) -> Result<(bool, SignedMessage), Error> {
+    let signed = SignedMessage::parse(encoded)?;
+    let valid = signed.verify()?;
 
     debug!(
-        "Message `{}` signed by `{}`: `{}` is {}!",
-        plain_message,
-        pub_key,
-        message_sig,
+        "{} message `{}` signed by `{}` is {}!",
+        signed.scheme.describe(),
+        signed.message,
+        signed.pubkey,
         match valid {
             true => "valid",
             _ => "invalid",
         }
     );
-    Ok((valid, plain_message, pub_key))
+    Ok((valid, signed))
 }
@@ -0,0 +1,52 @@
+//! Revocation of leaked proofs by their signers.
+//!
+//! A signer revokes a proof by setting the data entry `sqbadge.revoked.<id>` on its account,
+//! `<id>` being the first 48 hex characters of the sha256 hash of the proof message.
+use crate::stellar::stellar_data::Account;
+use crate::stellar::{stellar, transaction};
+use crate::util::anchor;
+use crate::util::error::Error;
+use crate::util::proof_encoding::SignedMessage;
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Prefix of the data entries marking a proof as revoked
+pub const DATA_PREFIX: &str = "sqbadge.revoked.";
+/// Value written to the data entry, any value counts
+const DATA_VALUE: &[u8] = b"1";
+/// Hex characters of the hash that fit the data entry name after the prefix
+pub const ID_LENGTH: usize = transaction::MAX_DATA_LENGTH - DATA_PREFIX.len();
+
+/// Identifies a proof on-chain, the first `ID_LENGTH` hex characters of its message hash
+pub fn proof_id(message: &str) -> String {
+    let mut id = hex::encode(anchor::proof_hash(message));
+    id.truncate(ID_LENGTH);
+    id
+}
+
+/// Name of the data entry revoking the proof `message`
+pub fn data_name(message: &str) -> String {
+    format!("{}{}", DATA_PREFIX, proof_id(message))
+}
+
+pub fn is_revoked_by(account: &Account, message: &str) -> bool {
+    account.data.contains_key(&data_name(message))
+}
+
+/// Builds the unsigned transaction revoking the proof `message` of `account`
+pub fn revoke_transaction(account: &str, sequence: &str, message: &str) -> Result<String> {
+    transaction::manage_data(account, sequence, &data_name(message), Some(DATA_VALUE))
+}
+
+/// Looks up the data entries of all signers, returns the first account that revoked the proof
+pub async fn check(signed: &SignedMessage) -> Result<Option<String>> {
+    for id in signed.accounts() {
+        let account = stellar::fetch_account(&id)
+            .await
+            .map_err(|err| err.context(format!("The account {} could not be checked", id)))?;
+        if is_revoked_by(&account, &signed.message) {
+            return Ok(Some(id));
+        }
+    }
+    Ok(None)
+}
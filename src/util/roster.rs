@@ -0,0 +1,209 @@
+//! Rosters: named lists of accounts ranked on a leaderboard.
+//!
+//! A roster is imported from a spreadsheet export, one member per line holding the account id
+//! and the display name separated by a comma, semicolon or tab. The roster and the last
+//! computed leaderboard are kept in the local storage, so they survive closing the browser.
+use crate::stellar::strkey;
+use crate::util::badge_check::Badge;
+use crate::util::error::Error;
+use crate::util::series;
+use itertools::Itertools;
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Local storage key of the cached roster
+const STORAGE_KEY: &str = "sqbadge.roster";
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Member {
+    pub account: String,
+    pub name: String,
+}
+
+/// Progress of a member, only earned badges count
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct Standing {
+    pub member: Member,
+    /// Distinct badges earned
+    pub completed: usize,
+    /// Completed series with the date their last quest badge was earned
+    pub series: Vec<(usize, String)>,
+    /// When the last badge was earned, the earlier the better on ties
+    pub last_earned: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct Leaderboard {
+    /// Best member first
+    pub standings: Vec<Standing>,
+    /// RFC 3339 time the badges were resolved at
+    pub updated_at: String,
+    /// Members whose badges could not be resolved, with the reason
+    pub failed: Vec<(Member, String)>,
+}
+
+/// What is kept between sessions
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct CachedRoster {
+    pub members: Vec<Member>,
+    pub leaderboard: Option<Leaderboard>,
+}
+
+/// Reads the members from a spreadsheet export.
+/// Empty lines, `#` comments and a header row without account id are skipped,
+/// the first line of a repeated account wins.
+pub fn parse(text: &str) -> Result<Vec<Member>> {
+    let mut members: Vec<Member> = vec![];
+    let mut first_row = true;
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields = line
+            .split([',', ';', '\t'])
+            .map(|f| f.trim().trim_matches('"').trim())
+            .collect::<Vec<&str>>();
+        let position = fields
+            .iter()
+            .position(|f| strkey::decode(strkey::VERSION_ACCOUNT_ID, f).is_some());
+        let header = first_row;
+        first_row = false;
+
+        let position = match position {
+            Some(position) => position,
+            None if header => continue,
+            None => {
+                return Err(Error::Other(format!(
+                    "Line {} of the roster holds no valid account id!",
+                    number + 1
+                )))
+            }
+        };
+        let account = String::from(fields[position]);
+        let name = fields
+            .iter()
+            .enumerate()
+            .filter(|(i, f)| *i != position && !f.is_empty())
+            .map(|(_, f)| *f)
+            .join(" ");
+        if members.iter().any(|m| m.account == account) {
+            continue;
+        }
+        members.push(Member {
+            name: if name.is_empty() {
+                account.clone()
+            } else {
+                name
+            },
+            account,
+        });
+    }
+
+    if members.is_empty() {
+        return Err(Error::Other(String::from("The roster holds no accounts!")));
+    }
+    Ok(members)
+}
+
+/// Earliest date a badge with `code` was earned at
+fn earned_at(badges: &[Badge], code: &str) -> Option<String> {
+    badges
+        .iter()
+        .filter(|b| b.token.code == code && b.is_earned())
+        .filter_map(|b| b.date_accuired.clone())
+        .min()
+}
+
+pub fn standing(member: &Member, badges: &[Badge]) -> Standing {
+    let earned = badges
+        .iter()
+        .filter(|b| b.is_earned())
+        .map(|b| b.token.code.clone())
+        .unique()
+        .collect::<Vec<String>>();
+
+    let catalog = badges.iter().map(|b| b.token.clone()).collect::<Vec<_>>();
+    let series = series::progress(&catalog, badges, Badge::is_earned)
+        .into_iter()
+        .filter_map(|series| Some((series.number, series.completed_at?)))
+        .collect();
+
+    Standing {
+        member: member.clone(),
+        completed: earned.len(),
+        series,
+        last_earned: earned
+            .iter()
+            .filter_map(|code| earned_at(badges, code))
+            .max(),
+    }
+}
+
+/// Ranks the members by earned badges, whoever got there first wins ties
+pub fn leaderboard(members: &[Member], badges: &[Vec<Badge>], updated_at: String) -> Leaderboard {
+    let standings = members
+        .iter()
+        .zip(badges)
+        .map(|(member, badges)| standing(member, badges))
+        .sorted_by(|a, b| {
+            b.completed
+                .cmp(&a.completed)
+                // equal counts above zero both have a date
+                .then_with(|| a.last_earned.cmp(&b.last_earned))
+                .then_with(|| a.member.name.cmp(&b.member.name))
+        })
+        .collect();
+    Leaderboard {
+        standings,
+        updated_at,
+        failed: vec![],
+    }
+}
+
+/// The first member to complete each series with the date, by series
+pub fn first_to_finish(leaderboard: &Leaderboard) -> Vec<(usize, Member, String)> {
+    leaderboard
+        .standings
+        .iter()
+        .flat_map(|s| {
+            s.series
+                .iter()
+                .map(move |(series, date)| (*series, s.member.clone(), date.clone()))
+        })
+        .sorted_by(|a, b| a.0.cmp(&b.0).then_with(|| a.2.cmp(&b.2)))
+        .unique_by(|(series, _, _)| *series)
+        .collect()
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+/// The roster and leaderboard of the last session
+pub fn load() -> Option<CachedRoster> {
+    let data = local_storage()?.get_item(STORAGE_KEY).ok()??;
+    serde_json::from_str(&data)
+        .map_err(|err| warn!("Dropping the cached roster: {}", err))
+        .ok()
+}
+
+pub fn store(roster: &CachedRoster) {
+    let stored = serde_json::to_string(roster)
+        .ok()
+        .and_then(|data| local_storage()?.set_item(STORAGE_KEY, &data).ok());
+    if stored.is_none() {
+        warn!("Could not cache the roster!");
+    }
+}
+
+pub fn clear() {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(STORAGE_KEY);
+    }
+}
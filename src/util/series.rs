@@ -0,0 +1,116 @@
+//! Quest series: the `SQnnxx` quest badges of series nn and the special `SSQnn` badge
+//! awarded once all of them are done.
+use crate::stellar::stellar_data::TOMLCurrency;
+use crate::util::badge_check::Badge;
+use crate::util::proof_encoding;
+use itertools::Itertools;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Series {
+    pub number: usize,
+    /// Codes of the quest badges in quest order
+    pub quests: Vec<String>,
+    /// Code of the badge for completing the series, if the catalog has one
+    pub special: Option<String>,
+    /// Quests whose badge meets the requirement
+    pub completed_quests: usize,
+    /// When the last quest badge was acquired, None unless the series is complete
+    pub completed_at: Option<String>,
+}
+
+impl Series {
+    /// The series in the catalog in series order, without progress
+    pub fn from_catalog(available_badges: &[TOMLCurrency]) -> Vec<Series> {
+        available_badges
+            .iter()
+            .filter_map(|c| Some((proof_encoding::quest_position(&c.code)?, &c.code)))
+            .sorted()
+            .dedup()
+            .group_by(|((series, _), _)| *series)
+            .into_iter()
+            .map(|(number, codes)| {
+                let (special, quests): (Vec<_>, Vec<_>) =
+                    codes.partition(|((_, quest), _)| *quest == 0);
+                Series {
+                    number,
+                    quests: quests.into_iter().map(|(_, code)| code.clone()).collect(),
+                    special: special.into_iter().map(|(_, code)| code.clone()).next(),
+                    ..Series::default()
+                }
+            })
+            .collect()
+    }
+
+    /// Progress of the resolved `badges`, a quest counts once a badge of it is `met`
+    pub fn with_progress<F>(mut self, badges: &[Badge], met: F) -> Series
+    where
+        F: Fn(&Badge) -> bool,
+    {
+        let dates = self
+            .quests
+            .iter()
+            .map(|code| {
+                let met = badges
+                    .iter()
+                    .filter(|b| &b.token.code == code && met(b))
+                    .collect::<Vec<&Badge>>();
+                match met.is_empty() {
+                    true => None,
+                    false => Some(met.iter().filter_map(|b| b.date_accuired.clone()).min()),
+                }
+            })
+            .collect::<Vec<Option<Option<String>>>>();
+
+        self.completed_quests = dates.iter().filter(|d| d.is_some()).count();
+        self.completed_at = match self.is_complete() {
+            true => dates
+                .into_iter()
+                .map(|date| date.flatten())
+                .collect::<Option<Vec<String>>>()
+                .and_then(|dates| dates.into_iter().max()),
+            false => None,
+        };
+        self
+    }
+
+    /// The display group of the badges of this series, see `group_key`
+    pub fn group(&self) -> String {
+        series_group(self.number)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        !self.quests.is_empty() && self.completed_quests == self.quests.len()
+    }
+
+    /// Completed quests in percent, rounded down
+    pub fn completion(&self) -> usize {
+        match self.quests.len() {
+            0 => 0,
+            total => self.completed_quests * 100 / total,
+        }
+    }
+}
+
+/// Progress in every series of the catalog, see `Series::with_progress`
+pub fn progress<F>(available_badges: &[TOMLCurrency], badges: &[Badge], met: F) -> Vec<Series>
+where
+    F: Fn(&Badge) -> bool,
+{
+    Series::from_catalog(available_badges)
+        .into_iter()
+        .map(|series| series.with_progress(badges, &met))
+        .collect()
+}
+
+/// Groups badge codes for display: quest and special badges by series (`SQ01`),
+/// others by their first four characters
+pub fn group_key(code: &str) -> String {
+    match proof_encoding::quest_position(code) {
+        Some((number, _)) => series_group(number),
+        None => code.chars().take(4).collect(),
+    }
+}
+
+fn series_group(number: usize) -> String {
+    format!("SQ{:02}", number)
+}
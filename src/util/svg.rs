@@ -0,0 +1,14 @@
+//! Helpers for the SVG images rendered in Rust.
+
+/// Escapes text for SVG markup, badge codes and urls come from a remote stellar.toml
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// `data:` url of an image, usable as `src` of an `img` or as download link
+pub fn data_url(mime: &str, data: &[u8]) -> String {
+    format!("data:{};base64,{}", mime, base64::encode(data))
+}
@@ -0,0 +1,136 @@
+//! The badges of an account in the order they were acquired, and a cumulative chart of them.
+use crate::util::badge_check::Badge;
+use crate::util::proof_encoding;
+use crate::util::svg;
+use chrono::{DateTime, Duration, Utc};
+use itertools::Itertools;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub code: String,
+    pub date: DateTime<Utc>,
+    /// None for badges outside the quest series
+    pub series: Option<usize>,
+}
+
+/// Owned badges with a known acquisition date, oldest first.
+/// Color and mono variants count once, at the earlier date.
+pub fn entries(badges: &[Badge]) -> Vec<Entry> {
+    badges
+        .iter()
+        .filter(|b| b.owned)
+        .filter_map(|b| {
+            let date = DateTime::parse_from_rfc3339(b.date_accuired.as_ref()?).ok()?;
+            Some(Entry {
+                code: b.token.code.clone(),
+                date: date.with_timezone(&Utc),
+                series: proof_encoding::quest_position(&b.token.code).map(|(series, _)| series),
+            })
+        })
+        .sorted_by(|a, b| a.date.cmp(&b.date).then_with(|| a.code.cmp(&b.code)))
+        .unique_by(|e| e.code.clone())
+        .collect()
+}
+
+/// Groups the entries by series in series order, badges outside the series last
+pub fn by_series(entries: &[Entry]) -> Vec<(Option<usize>, Vec<&Entry>)> {
+    entries
+        .iter()
+        .sorted_by_key(|e| (e.series.is_none(), e.series))
+        .group_by(|e| e.series)
+        .into_iter()
+        .map(|(series, entries)| (series, entries.collect()))
+        .collect()
+}
+
+/// Mean time between two consecutive badges, None with less than two badges
+pub fn average_interval(entries: &[Entry]) -> Option<Duration> {
+    if entries.len() < 2 {
+        return None;
+    }
+    let (first, last) = (entries.first()?, entries.last()?);
+    Some((last.date - first.date) / (entries.len() as i32 - 1))
+}
+
+/// `3 days 4 hours`, leaving out minutes once the duration spans days
+pub fn describe_duration(duration: Duration) -> String {
+    let plural = |count: i64, unit: &str| match count {
+        1 => format!("1 {}", unit),
+        _ => format!("{} {}s", count, unit),
+    };
+    let (days, hours, minutes) = (
+        duration.num_days(),
+        duration.num_hours() % 24,
+        duration.num_minutes() % 60,
+    );
+    match (days, hours) {
+        (0, 0) => plural(minutes, "minute"),
+        (0, _) => format!("{} {}", plural(hours, "hour"), plural(minutes, "minute")),
+        (_, 0) => plural(days, "day"),
+        _ => format!("{} {}", plural(days, "day"), plural(hours, "hour")),
+    }
+}
+
+const MARGIN: f64 = 40.0;
+
+/// Step chart of the amount of badges owned over time, `width` × `height` pixels
+pub fn chart_svg(entries: &[Entry], width: u32, height: u32) -> String {
+    let (w, h) = (width as f64, height as f64);
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="12">"#,
+        w = width,
+        h = height
+    );
+    svg.push_str(&format!(
+        r##"<path d="M{m} {m}V{b}H{r}" fill="none" stroke="#888"/>"##,
+        m = MARGIN,
+        b = h - MARGIN,
+        r = w - MARGIN
+    ));
+
+    if let (Some(first), Some(last)) = (entries.first(), entries.last()) {
+        let start = first.date.timestamp() as f64;
+        let span = (last.date.timestamp() as f64 - start).max(1.0);
+        let x = |date: &DateTime<Utc>| {
+            MARGIN + (date.timestamp() as f64 - start) / span * (w - 2.0 * MARGIN)
+        };
+        let y =
+            |count: usize| h - MARGIN - count as f64 / entries.len() as f64 * (h - 2.0 * MARGIN);
+
+        let mut path = format!("M{:.1} {:.1}", x(&first.date), y(0));
+        for (count, entry) in entries.iter().enumerate() {
+            path.push_str(&format!("H{:.1}V{:.1}", x(&entry.date), y(count + 1)));
+        }
+        svg.push_str(&format!(
+            r##"<path d="{}" fill="none" stroke="#00d1b2" stroke-width="2"/>"##,
+            path
+        ));
+        for (count, entry) in entries.iter().enumerate() {
+            svg.push_str(&format!(
+                r##"<circle cx="{:.1}" cy="{:.1}" r="3" fill="#00d1b2"><title>{} {}</title></circle>"##,
+                x(&entry.date),
+                y(count + 1),
+                svg::escape(&entry.code),
+                entry.date.format("%Y-%m-%d")
+            ));
+        }
+        svg.push_str(&format!(
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="end">{}</text>"#,
+            MARGIN - 4.0,
+            y(entries.len()) + 4.0,
+            entries.len()
+        ));
+        svg.push_str(&format!(
+            r#"<text x="{m}" y="{:.1}">{}</text><text x="{:.1}" y="{:.1}" text-anchor="end">{}</text>"#,
+            h - MARGIN + 16.0,
+            first.date.format("%Y-%m-%d"),
+            w - MARGIN,
+            h - MARGIN + 16.0,
+            last.date.format("%Y-%m-%d"),
+            m = MARGIN
+        ));
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
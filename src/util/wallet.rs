@@ -0,0 +1,248 @@
+use crate::js::{albedo, albedo_response, freighter, freighter_response};
+use crate::stellar::stellar;
+use crate::stellar::transaction::PUBLIC_NETWORK_PASSPHRASE;
+use crate::util::error::{Error, WalletErr};
+#[cfg(feature = "dev-signer")]
+use crate::util::local_signer;
+pub use crate::util::proof_encoding::SignedMessage;
+use crate::util::proof_encoding::{SignatureScheme, PUBLIC_NETWORK};
+use futures::future::{FutureExt, LocalBoxFuture};
+use gloo_utils::format::JsValueSerdeExt;
+use js_sys::JsString;
+use log::{debug, warn};
+use wasm_bindgen::JsValue;
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Session storage key remembering the wallet picked on the home page
+const STORAGE_KEY: &str = "sqbadge.wallet";
+
+pub trait WalletProvider {
+    fn kind(&self) -> WalletKind;
+    fn public_key(&self) -> LocalBoxFuture<'static, Result<String>>;
+    fn sign_message(
+        &self,
+        pub_key: &str,
+        message: &str,
+    ) -> LocalBoxFuture<'static, Result<SignedMessage>>;
+    /// Signs the transaction envelope and submits it to the public network
+    fn sign_transaction(&self, pub_key: &str, xdr: &str) -> LocalBoxFuture<'static, Result<()>>;
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum WalletKind {
+    #[default]
+    Albedo,
+    Freighter,
+    /// Secret seed held in memory, see `local_signer`
+    #[cfg(feature = "dev-signer")]
+    Local,
+}
+
+impl WalletKind {
+    /// Wallets living in the browser, offered on the home page
+    pub const BROWSER: [WalletKind; 2] = [WalletKind::Albedo, WalletKind::Freighter];
+    #[cfg(not(feature = "dev-signer"))]
+    pub const ALL: [WalletKind; 2] = WalletKind::BROWSER;
+    #[cfg(feature = "dev-signer")]
+    pub const ALL: [WalletKind; 3] = [WalletKind::Albedo, WalletKind::Freighter, WalletKind::Local];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WalletKind::Albedo => "Albedo",
+            WalletKind::Freighter => "Freighter",
+            #[cfg(feature = "dev-signer")]
+            WalletKind::Local => "Local key",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<WalletKind> {
+        WalletKind::ALL.iter().find(|k| k.name() == name).copied()
+    }
+
+    pub fn provider(&self) -> Box<dyn WalletProvider> {
+        match self {
+            WalletKind::Albedo => Box::new(Albedo),
+            WalletKind::Freighter => Box::new(Freighter),
+            #[cfg(feature = "dev-signer")]
+            WalletKind::Local => Box::new(local_signer::Local),
+        }
+    }
+}
+
+fn session_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.session_storage().ok()?
+}
+
+/// Remembers the wallet used to log in, so later signing requests go through it
+pub fn connect(kind: WalletKind) {
+    if let Some(storage) = session_storage() {
+        if storage.set_item(STORAGE_KEY, kind.name()).is_err() {
+            warn!("Could not remember the connected wallet!");
+        }
+    }
+}
+
+/// The wallet used to log in, albedo if the account page was opened directly
+pub fn connected() -> WalletKind {
+    session_storage()
+        .and_then(|s| s.get_item(STORAGE_KEY).ok().flatten())
+        .and_then(|name| WalletKind::from_name(&name))
+        .unwrap_or_default()
+}
+
+pub struct Albedo;
+
+fn albedo_error(err: JsValue) -> Error {
+    match JsValueSerdeExt::into_serde::<albedo_response::AlbedoError>(&err) {
+        Ok(err) => Error::WalletErr(WalletErr::Rejected(err.message)),
+        Err(_) => Error::WalletErr(WalletErr::InvalidResponse),
+    }
+}
+
+impl WalletProvider for Albedo {
+    fn kind(&self) -> WalletKind {
+        WalletKind::Albedo
+    }
+
+    fn public_key(&self) -> LocalBoxFuture<'static, Result<String>> {
+        async {
+            let token = "stellar.badge.rs";
+            let resp = albedo::public_key(JsString::from(token))
+                .await
+                .map_err(albedo_error)?;
+            let resp: albedo_response::AlbedoPublicKey = JsValueSerdeExt::into_serde(&resp)
+                .map_err(|_| Error::WalletErr(WalletErr::InvalidResponse))?;
+            Ok(resp.pubkey)
+        }
+        .boxed_local()
+    }
+
+    fn sign_message(
+        &self,
+        pub_key: &str,
+        message: &str,
+    ) -> LocalBoxFuture<'static, Result<SignedMessage>> {
+        let pub_key = String::from(pub_key);
+        let message = String::from(message);
+        async move {
+            let resp =
+                albedo::sign_message_pubkey(JsString::from(message), JsString::from(pub_key))
+                    .await
+                    .map_err(albedo_error)?;
+            let resp: albedo_response::AlbedoSignedMessage = JsValueSerdeExt::into_serde(&resp)
+                .map_err(|err| {
+                    warn!("Unexpected albedo response: {:?}", err);
+                    Error::WalletErr(WalletErr::InvalidResponse)
+                })?;
+            if resp.intent != "sign_message" {
+                return Err(Error::WalletErr(WalletErr::InvalidResponse));
+            }
+            Ok(SignedMessage {
+                scheme: SignatureScheme::Albedo,
+                network: String::from(PUBLIC_NETWORK),
+                pubkey: resp.pubkey,
+                message: resp.message,
+                signature: resp.message_signature,
+                anchor: None,
+                cosigners: vec![],
+            })
+        }
+        .boxed_local()
+    }
+
+    fn sign_transaction(&self, pub_key: &str, xdr: &str) -> LocalBoxFuture<'static, Result<()>> {
+        let pub_key = String::from(pub_key);
+        let xdr = String::from(xdr);
+        async move {
+            // albedo submits the transaction itself
+            let resp = albedo::sign_transaction(
+                JsString::from(xdr),
+                JsString::from(pub_key),
+                JsString::from("public"),
+                JsString::from(""),
+                true,
+            )
+            .await
+            .map_err(albedo_error)?;
+            debug!("{:?}", resp);
+            Ok(())
+        }
+        .boxed_local()
+    }
+}
+
+pub struct Freighter;
+
+fn freighter_error(err: JsValue) -> Error {
+    match JsValueSerdeExt::into_serde::<freighter_response::FreighterError>(&err) {
+        Ok(err) if err.code == -1 => Error::WalletErr(WalletErr::NotInstalled),
+        Ok(err) => Error::WalletErr(WalletErr::Rejected(err.message)),
+        Err(_) => Error::WalletErr(WalletErr::InvalidResponse),
+    }
+}
+
+impl WalletProvider for Freighter {
+    fn kind(&self) -> WalletKind {
+        WalletKind::Freighter
+    }
+
+    fn public_key(&self) -> LocalBoxFuture<'static, Result<String>> {
+        async {
+            let resp = freighter::public_key().await.map_err(freighter_error)?;
+            resp.as_string()
+                .ok_or(Error::WalletErr(WalletErr::InvalidResponse))
+        }
+        .boxed_local()
+    }
+
+    fn sign_message(
+        &self,
+        pub_key: &str,
+        message: &str,
+    ) -> LocalBoxFuture<'static, Result<SignedMessage>> {
+        let pub_key = String::from(pub_key);
+        let message = String::from(message);
+        async move {
+            let resp = freighter::sign_message(JsString::from(message), JsString::from(pub_key))
+                .await
+                .map_err(freighter_error)?;
+            let resp: freighter_response::FreighterSignedMessage =
+                JsValueSerdeExt::into_serde(&resp).map_err(|err| {
+                    warn!("Unexpected freighter response: {:?}", err);
+                    Error::WalletErr(WalletErr::InvalidResponse)
+                })?;
+            Ok(SignedMessage {
+                scheme: SignatureScheme::Sep53,
+                network: String::from(PUBLIC_NETWORK),
+                pubkey: resp.pubkey,
+                message: resp.message,
+                signature: resp.message_signature,
+                anchor: None,
+                cosigners: vec![],
+            })
+        }
+        .boxed_local()
+    }
+
+    fn sign_transaction(&self, pub_key: &str, xdr: &str) -> LocalBoxFuture<'static, Result<()>> {
+        let pub_key = String::from(pub_key);
+        let xdr = String::from(xdr);
+        async move {
+            let signed = freighter::sign_transaction(
+                JsString::from(xdr),
+                JsString::from(pub_key),
+                JsString::from(PUBLIC_NETWORK_PASSPHRASE),
+            )
+            .await
+            .map_err(freighter_error)?
+            .as_string()
+            .ok_or(Error::WalletErr(WalletErr::InvalidResponse))?;
+            // freighter only signs, the transaction still has to be submitted
+            let hash = stellar::submit_transaction(&signed).await?;
+            debug!("submitted transaction {}", hash);
+            Ok(())
+        }
+        .boxed_local()
+    }
+}
@@ -1,4 +1,5 @@
-use crate::util::badge_check::Badge;
+use crate::util::badge_check::{Badge, Provenance};
+use crate::webpage::view::{go_to, Route};
 
 use yew::prelude::*;
 
@@ -6,11 +7,18 @@ use yew::prelude::*;
 pub struct Props {
     pub badge: Badge,
     pub valid: bool,
+    #[prop_or_default]
+    pub on_claim: Option<Callback<Badge>>,
+    /// Show which account the badge was found in, for proofs over several accounts
+    #[prop_or_default]
+    pub show_account: bool,
 }
 
 pub struct BadgeCard {
     pub badge: Badge,
     pub valid: bool,
+    pub on_claim: Option<Callback<Badge>>,
+    pub show_account: bool,
 }
 
 impl Component for BadgeCard {
@@ -21,6 +29,8 @@ impl Component for BadgeCard {
         Self {
             badge: props.badge.to_owned(),
             valid: props.valid,
+            on_claim: props.on_claim,
+            show_account: props.show_account,
         }
     }
 
@@ -28,13 +38,24 @@ impl Component for BadgeCard {
         false
     }
 
-    fn change(&mut self, _: Self::Properties) -> ShouldRender {
-        false
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.badge == props.badge
+            && self.valid == props.valid
+            && self.on_claim == props.on_claim
+            && self.show_account == props.show_account
+        {
+            return false;
+        }
+        self.badge = props.badge;
+        self.valid = props.valid;
+        self.on_claim = props.on_claim;
+        self.show_account = props.show_account;
+        true
     }
 
     fn view(&self) -> Html {
         let mut cls = vec!["badge"];
-        if !self.badge.owned {
+        if !self.badge.owned && !self.badge.pending {
             cls.push("disabled");
         }
 
@@ -45,22 +66,77 @@ impl Component for BadgeCard {
             monochrome = "(monochrome) "
         }
 
+        if self.badge.pending {
+            cls.push("pending");
+        }
+        if self.badge.owned && !self.badge.is_earned() {
+            cls.push("not-earned");
+        }
+        if self.badge.owned && !self.badge.held {
+            cls.push("sent-away");
+        }
+
+        let mut provenance = match self.badge.provenance.clone() {
+            Some(p) => format!(" ({})", p.describe()),
+            None => String::default(),
+        };
+        if self.badge.owned && !self.badge.held {
+            provenance.push_str(&match self.badge.sent_to.clone() {
+                Some(to) => format!(", no longer held (sent to {})", to),
+                None => String::from(", no longer held"),
+            });
+        }
+
         let inner = html! {
             <>
                 <img style="margin-left: auto; margin-right: auto; display: block;" src={self.badge.token.image.clone()}
                  title={
-                     match self.badge.date_accuired.clone() {
-                         Some(date) => format!("{} {}owned since {}", &self.badge.token.code, monochrome, date),
-                         None => format!("{} not accuired yet", &name)
+                     match (self.badge.owned, self.badge.date_accuired.clone()) {
+                         (true, Some(date)) => format!("{} {}owned since {}{}", &self.badge.token.code, monochrome, date, provenance),
+                         (true, None) => format!("{} {}owned{}", &self.badge.token.code, monochrome, provenance),
+                         (false, _) if self.badge.pending => format!("{} is ready to be claimed", &name),
+                         _ => format!("{} not accuired yet", &name)
                      }
                  } alt="" />
                 <p class="badge-name">{&name}</p>
+                {
+                    match self.badge.provenance.clone() {
+                        Some(Provenance::Transferred { from: _ }) => html! { <p class="badge-provenance">{"transferred"}</p> },
+                        Some(Provenance::Unexplained) => html! { <p class="badge-provenance">{"unverified"}</p> },
+                        _ if self.badge.owned && !self.badge.held => html! { <p class="badge-provenance">{"sent away"}</p> },
+                        _ => Html::default(),
+                    }
+                }
+                {
+                    if self.show_account && self.badge.owned {
+                        html! { <p class="badge-account" title={self.badge.account.clone()}>{short_account(&self.badge.account)}</p> }
+                    } else {
+                        Html::default()
+                    }
+                }
+                {
+                    if self.badge.pending {
+                        self.view_claim()
+                    } else {
+                        Html::default()
+                    }
+                }
             </>
         };
-        let outer = if self.badge.owned {
+        let outer = if self.badge.owned && !self.badge.account.is_empty() {
+            let route = Route::Badge {
+                account: self.badge.account.clone(),
+                code: self.badge.token.code.clone(),
+            };
             html! {
                 <div class={classes!(cls)}>
-                    <a href={format!("https://stellar.expert/explorer/public/tx/{}", self.badge.tx_hash.clone().unwrap())} target={"_blank"}>{ inner }</a>
+                    { go_to(route, inner, vec![]) }
+                </div>
+            }
+        } else if let (true, Some(tx_hash)) = (self.badge.owned, &self.badge.tx_hash) {
+            html! {
+                <div class={classes!(cls)}>
+                    <a href={format!("https://stellar.expert/explorer/public/tx/{}", tx_hash)} target={"_blank"}>{ inner }</a>
                 </div>
             }
         } else {
@@ -80,3 +156,30 @@ impl Component for BadgeCard {
         }
     }
 }
+
+impl BadgeCard {
+    fn view_claim(&self) -> Html {
+        match self.on_claim.clone() {
+            Some(on_claim) => {
+                let badge = self.badge.clone();
+                html! {
+                    <button class="button is-small is-primary badge-claim" onclick={Callback::from(move |_| on_claim.emit(badge.clone()))}>
+                        {"Claim"}
+                    </button>
+                }
+            }
+            None => html! { <p class="badge-provenance">{"ready to claim"}</p> },
+        }
+    }
+}
+
+/// `GABC…WXYZ`
+pub fn short_account(account: &str) -> String {
+    match (
+        account.get(..4),
+        account.get(account.len().saturating_sub(4)..),
+    ) {
+        (Some(start), Some(end)) if account.len() > 8 => format!("{}…{}", start, end),
+        _ => account.to_string(),
+    }
+}
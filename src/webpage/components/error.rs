@@ -5,10 +5,14 @@ use yew::prelude::*;
 #[derive(Clone, Debug, PartialEq, Properties)]
 pub struct Props {
     pub message: String,
+    /// Machine readable error code, see `Error::code`
+    #[prop_or_default]
+    pub code: Option<&'static str>,
 }
 
 pub struct ErrorCard {
     pub message: String,
+    pub code: Option<&'static str>,
 }
 
 impl Component for ErrorCard {
@@ -18,6 +22,7 @@ impl Component for ErrorCard {
     fn create(props: Self::Properties, _: ComponentLink<Self>) -> Self {
         Self {
             message: props.message,
+            code: props.code,
         }
     }
 
@@ -35,6 +40,12 @@ impl Component for ErrorCard {
                <div class="notification is-danger" style="width: auto">
                    <p style="font-size: 1.5rem; font-weight: 500;">{"Error:"}</p>
                    <p style="text-align: center; ">{&self.message}</p>
+                   {
+                       match self.code {
+                           Some(code) => html! { <p class="is-size-7" style="text-align: center; ">{format!("({})", code)}</p> },
+                           None => Html::default(),
+                       }
+                   }
                </div>
            </div>
         }
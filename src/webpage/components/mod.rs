@@ -1,2 +1,4 @@
 pub mod badge;
 pub mod error;
+pub mod series;
+pub mod timeline;
@@ -0,0 +1,35 @@
+use crate::util::series::Series;
+
+use yew::prelude::*;
+
+/// Classes of the section holding the badges of a series, complete series are highlighted
+pub fn series_classes(series: Option<&Series>) -> Classes {
+    match series {
+        Some(series) if series.is_complete() => classes!("section", "series-complete"),
+        _ => classes!("section"),
+    }
+}
+
+/// Title of a group of badges, with the progress if the group is a quest series
+pub fn view_series_title(group: &str, series: Option<&Series>) -> Html {
+    let series = match series {
+        Some(series) => series,
+        None => return html! { <h1 class="title" style="text-align: center">{group}</h1> },
+    };
+    let progress = match (series.is_complete(), &series.completed_at) {
+        (true, Some(date)) => format!("complete since {}", date),
+        (true, None) => String::from("complete"),
+        (false, _) => format!(
+            "{}/{} quests ({}%)",
+            series.completed_quests,
+            series.quests.len(),
+            series.completion()
+        ),
+    };
+    html! {
+        <>
+            <h1 class="title" style="text-align: center">{format!("Series {}", series.number)}</h1>
+            <p class="subtitle series-progress">{progress}</p>
+        </>
+    }
+}
@@ -0,0 +1,89 @@
+use crate::util::badge_check::Badge;
+use crate::util::svg;
+use crate::util::timeline::{self, Entry};
+
+use yew::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Properties)]
+pub struct Props {
+    pub badges: Vec<Badge>,
+}
+
+/// Badges of an account in the order they were acquired
+pub struct Timeline {
+    pub entries: Vec<Entry>,
+}
+
+impl Component for Timeline {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(props: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Self {
+            entries: timeline::entries(&props.badges),
+        }
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let entries = timeline::entries(&props.badges);
+        if self.entries == entries {
+            return false;
+        }
+        self.entries = entries;
+        true
+    }
+
+    fn view(&self) -> Html {
+        if self.entries.is_empty() {
+            return html! { <p style="text-align: center">{"No badges acquired yet."}</p> };
+        }
+        let chart = timeline::chart_svg(&self.entries, 720, 280);
+        html! {
+            <section class="section">
+                <p style="text-align: center">
+                    {format!("{} badges between {} and {}",
+                        self.entries.len(),
+                        self.entries[0].date.format("%Y-%m-%d"),
+                        self.entries[self.entries.len() - 1].date.format("%Y-%m-%d"))}
+                    {
+                        match timeline::average_interval(&self.entries) {
+                            Some(interval) => format!(", one every {} on average", timeline::describe_duration(interval)),
+                            None => String::default(),
+                        }
+                    }
+                </p>
+                <img style="display: block; margin: 1rem auto; max-width: 100%"
+                     src={svg::data_url("image/svg+xml", chart.as_bytes())}
+                     alt="Badges acquired over time" />
+                {
+                    timeline::by_series(&self.entries).into_iter().map(|(series, entries)| html! {
+                        <>
+                            <h3 class="title is-5">
+                                {
+                                    match series {
+                                        Some(series) => format!("Series {}", series),
+                                        None => String::from("Other badges"),
+                                    }
+                                }
+                            </h3>
+                            <ul class="mb-4">
+                            {
+                                entries.into_iter().map(|entry| html! {
+                                    <li>
+                                        <strong>{entry.date.format("%Y-%m-%d %H:%M").to_string()}</strong>
+                                        {format!(" {}", entry.code)}
+                                    </li>
+                                }).collect::<Html>()
+                            }
+                            </ul>
+                        </>
+                    }).collect::<Html>()
+                }
+            </section>
+        }
+    }
+}
@@ -1,21 +1,30 @@
 use chrono::Utc;
-use js_sys::JsString;
 use log::{debug, warn};
-use serde_json::Value;
-use wasm_bindgen::JsValue;
 use yew::prelude::*;
 use yew::{html, Component, ComponentLink};
 
-use base64;
-
-use crate::js::albedo;
+use crate::js::fetch;
 use crate::stellar::stellar_data::TOMLCurrency;
+use crate::stellar::transaction;
 use crate::stellar::*;
-use crate::util::badge_check::{self, Badge};
+use crate::util::anchor;
+use crate::util::badge_check::{self, Badge, OwnershipRequirement};
+use crate::util::badge_wall;
+use crate::util::embed::{self, EmbedOptions};
 use crate::util::error::{Error, StellarErr};
-use crate::util::proof_encoding::Proof;
+use crate::util::proof_encoding::{self, Cosignature, Predicate, Proof};
+use crate::util::revocation;
+use crate::util::series::{self, Series};
+use crate::util::svg;
+use crate::util::wallet::{self, SignedMessage};
+use crate::webpage::components::badge::BadgeCard;
 use crate::webpage::components::error::ErrorCard;
+use crate::webpage::components::series::{series_classes, view_series_title};
+use crate::webpage::components::timeline::Timeline;
+use crate::webpage::pages::embed::app_base;
+use crate::webpage::view::{go_to, Route};
 use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Clone, Debug, Eq, PartialEq, Properties)]
 pub struct Props {
@@ -34,7 +43,23 @@ pub struct AccountView {
     status: WorkFunction,
     storage: AccountStorage,
     signing_message: String,
+    cosigners: String,
+    /// owned badge codes left out of the proof
+    undisclosed: HashSet<String>,
+    predicates: Vec<Predicate>,
+    min_badges: Option<usize>,
     modal_shown: bool,
+    /// show the badges in the order they were acquired instead of by series
+    show_timeline: bool,
+    /// SVG image of the owned badges, once rendered
+    badge_wall: Option<String>,
+    /// show the snippets embedding the badges in other sites
+    show_embed: bool,
+    anchor_proof: bool,
+    revoking_proof: String,
+    /// id of the revoked proof
+    revoke_result: Option<Result<String, Error>>,
+    claim_error: Option<String>,
 }
 
 #[derive(PartialEq, Clone, Debug)]
@@ -47,10 +72,26 @@ pub enum WorkFunction {
     Done,
     None,
     ToggleModal,
+    ToggleTimeline,
+    RenderBadgeWall,
+    BadgeWallDone(String),
+    CloseBadgeWall,
+    ToggleEmbed,
     ModalProofTextChange(String),
+    ModalCosignersChange(String),
+    ModalMinBadgesChange(String),
+    ToggleDisclosed(String),
+    ToggleSeriesDisclosed(usize),
+    TogglePredicate(Predicate),
+    ToggleAnchor,
     CreateProof,
-    ProofSignDone(Result<JsValue, JsValue>),
-    Err(String),
+    ProofSignDone(Result<SignedMessage, Error>),
+    ClaimBadge(Badge),
+    ClaimSignDone(Result<(), Error>),
+    ModalRevokeChange(String),
+    RevokeProof,
+    RevokeDone(Result<String, Error>),
+    Err(Error),
 }
 
 impl Component for AccountView {
@@ -64,7 +105,18 @@ impl Component for AccountView {
             status: WorkFunction::Begin,
             storage: AccountStorage::default(),
             signing_message: String::new(),
+            cosigners: String::new(),
+            undisclosed: HashSet::new(),
+            predicates: vec![],
+            min_badges: None,
             modal_shown: false,
+            show_timeline: false,
+            badge_wall: None,
+            show_embed: false,
+            anchor_proof: false,
+            revoking_proof: String::new(),
+            revoke_result: None,
+            claim_error: None,
         }
     }
 
@@ -74,8 +126,8 @@ impl Component for AccountView {
         }
 
         if !check_valid_public_key(&self.props.account) {
-            self.link.send_message(WorkFunction::Err(String::from(
-                "Invalid ed25519 public key!",
+            self.link.send_message(WorkFunction::Err(Error::StellarErr(
+                StellarErr::InvalidPublicKey,
             )));
             return;
         }
@@ -95,8 +147,14 @@ impl Component for AccountView {
                     let badges = stellar::fetch_toml_currencies(&String::from(
                         "https://quest.stellar.org/.well-known/stellar.toml",
                     ))
-                    .await
-                    .unwrap();
+                    .await;
+                    let badges =
+                        match badges {
+                            Some(badges) => badges,
+                            None => return WorkFunction::Err(Error::Other(String::from(
+                                "The available badges could not be loaded from quest.stellar.org!",
+                            ))),
+                        };
                     let badges = badges
                         .into_iter()
                         .filter(|b| b.code.starts_with("SQ") || b.code.starts_with("SSQ"))
@@ -117,44 +175,22 @@ impl Component for AccountView {
                 let pub_key = self.props.account.clone();
                 let available_badges = self.storage.available_badges.clone();
 
-                if available_badges.is_none() {
-                    warn!("Invalid load state: available badges are None!");
-                    self.link.send_message(WorkFunction::Err(String::from(
-                        "Invalid data received for: available_badges in None!",
-                    )));
-                    return false;
-                }
-
-                let available_badges = available_badges.unwrap();
-
-                self.link.send_future(async move {
-                    let in_possession =
-                        badge_check::fetch_badges(&pub_key, &available_badges).await;
-
-                    if in_possession.is_err() {
-                        // Sth went wrong fetching --> probably wrong account id (if not handled inbefore ._.)
-                        let err =  in_possession.err().unwrap();
-
-                        if let Error::StellarErr(s_err) = err  {
-                            return WorkFunction::Err(match s_err {
-                                StellarErr::AccountNotFound => {
-                                    format!("The account you specified could not be found!")
-                                },
-                                StellarErr::InvalidPublicKey => {
-                                    format!("The specified public key is not in a valid ed25519 format!")
-                                },
-                                _ => {
-                                    format!("Unknown error while trying to connect to the stellar network!")
-                                }
-
-                            });
-                        }
-
-                        return WorkFunction::Err(format!("{:?}", err));
+                let available_badges = match available_badges {
+                    Some(available_badges) => available_badges,
+                    None => {
+                        warn!("Invalid load state: available badges are None!");
+                        self.link
+                            .send_message(WorkFunction::Err(Error::Other(String::from(
+                                "Invalid data received for: available_badges in None!",
+                            ))));
+                        return false;
                     }
+                };
 
-                    WorkFunction::FetchOwnedBadgesDone {
-                        owned_badges: in_possession.unwrap(),
+                self.link.send_future(async move {
+                    match badge_check::fetch_badges(&pub_key, &available_badges).await {
+                        Ok(owned_badges) => WorkFunction::FetchOwnedBadgesDone { owned_badges },
+                        Err(err) => WorkFunction::Err(err),
                     }
                 });
                 false
@@ -174,6 +210,11 @@ impl Component for AccountView {
                 self.modal_shown = !self.modal_shown;
 
                 self.signing_message = String::new();
+                self.cosigners = String::new();
+                self.revoking_proof = String::new();
+                self.undisclosed = HashSet::new();
+                self.predicates = vec![];
+                self.min_badges = None;
                 self.status = WorkFunction::Done;
                 true
             }
@@ -181,6 +222,81 @@ impl Component for AccountView {
                 self.signing_message = msg;
                 false
             }
+            WorkFunction::ModalCosignersChange(cosigners) => {
+                self.cosigners = cosigners;
+                false
+            }
+            WorkFunction::ModalMinBadgesChange(min_badges) => {
+                self.min_badges = min_badges.trim().parse().ok().filter(|n| *n > 0);
+                false
+            }
+            WorkFunction::ToggleDisclosed(code) => {
+                if !self.undisclosed.remove(&code) {
+                    self.undisclosed.insert(code);
+                }
+                self.status = WorkFunction::Done;
+                true
+            }
+            WorkFunction::ToggleSeriesDisclosed(series) => {
+                let codes = self
+                    .owned_codes()
+                    .into_iter()
+                    .filter(|code| series_of(code) == Some(series))
+                    .collect::<Vec<String>>();
+                let disclosed = codes.iter().any(|code| !self.undisclosed.contains(code));
+                for code in codes {
+                    match disclosed {
+                        true => self.undisclosed.insert(code),
+                        false => self.undisclosed.remove(&code),
+                    };
+                }
+                self.status = WorkFunction::Done;
+                true
+            }
+            WorkFunction::TogglePredicate(predicate) => {
+                match self.predicates.iter().position(|p| p == &predicate) {
+                    Some(index) => {
+                        self.predicates.remove(index);
+                    }
+                    None => self.predicates.push(predicate),
+                }
+                self.status = WorkFunction::Done;
+                true
+            }
+            WorkFunction::ToggleTimeline => {
+                self.show_timeline = !self.show_timeline;
+                self.status = WorkFunction::Done;
+                true
+            }
+            WorkFunction::RenderBadgeWall => {
+                let account = self.props.account.clone();
+                let badges = self.storage.owned_badges.clone().unwrap_or_default();
+                self.link.send_future(async move {
+                    WorkFunction::BadgeWallDone(render_badge_wall(&account, &badges).await)
+                });
+                self.status = WorkFunction::Done;
+                false
+            }
+            WorkFunction::BadgeWallDone(wall) => {
+                self.badge_wall = Some(wall);
+                self.status = WorkFunction::Done;
+                true
+            }
+            WorkFunction::CloseBadgeWall => {
+                self.badge_wall = None;
+                self.status = WorkFunction::Done;
+                true
+            }
+            WorkFunction::ToggleEmbed => {
+                self.show_embed = !self.show_embed;
+                self.status = WorkFunction::Done;
+                true
+            }
+            WorkFunction::ToggleAnchor => {
+                self.anchor_proof = !self.anchor_proof;
+                self.status = WorkFunction::Done;
+                true
+            }
             WorkFunction::CreateProof => {
                 let mut proof = Proof::default();
                 proof.timestamp = Some(Utc::now().timestamp());
@@ -195,24 +311,39 @@ impl Component for AccountView {
                     .clone()
                     .unwrap_or(vec![])
                     .into_iter()
-                    .filter(|b| b.owned)
+                    .filter(|b| b.owned && !self.undisclosed.contains(&b.token.code))
                     .map(|b| b.token.clone())
                     .collect();
-                let data = proof.encode_v1();
-                if data.is_ok() {
-                    let data = data.unwrap();
-                    let pub_key = self.props.account.clone();
-                    self.link.send_future(async {
-                        let albedo_response = unsafe {
-                            albedo::sign_message_pubkey(
-                                JsString::from(data),
-                                JsString::from(pub_key),
-                            )
-                            .await
-                        };
-                        WorkFunction::ProofSignDone(albedo_response)
-                    });
+                proof.predicates = self.predicates.clone();
+                if let Some(min_badges) = self.min_badges {
+                    proof.predicates.push(Predicate::AtLeast(min_badges));
                 }
+                let pub_key = self.props.account.clone();
+                let cosigners = self
+                    .cosigners
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|c| !c.is_empty() && c != &pub_key)
+                    .map(String::from)
+                    .unique()
+                    .collect::<Vec<String>>();
+                let available_badges = self.storage.available_badges.clone().unwrap_or_default();
+                let undisclosed = self.undisclosed.clone();
+                let anchored = self.anchor_proof;
+                self.link.send_future(async move {
+                    let signed = match sign_proof(
+                        &pub_key,
+                        &cosigners,
+                        &undisclosed,
+                        proof,
+                        &available_badges,
+                    )
+                    .await
+                    {
+                        Ok(signed) if anchored => anchor_proof(&pub_key, signed).await,
+                        signed => signed,
+                    };
+                    WorkFunction::ProofSignDone(signed)
+                });
                 self.modal_shown = false;
                 self.status = WorkFunction::Done;
                 true
@@ -225,6 +356,59 @@ impl Component for AccountView {
                 }
                 true
             }
+            WorkFunction::ClaimBadge(badge) => {
+                let pub_key = self.props.account.clone();
+                self.claim_error = None;
+                self.link.send_future(async move {
+                    let xdr = match build_claim_transaction(&pub_key, &badge).await {
+                        Ok(xdr) => xdr,
+                        Err(err) => return WorkFunction::ClaimSignDone(Err(err)),
+                    };
+                    let submitted = wallet::connected()
+                        .provider()
+                        .sign_transaction(&pub_key, &xdr)
+                        .await;
+                    WorkFunction::ClaimSignDone(submitted)
+                });
+                self.status = WorkFunction::Done;
+                false
+            }
+            WorkFunction::ClaimSignDone(response) => {
+                match response {
+                    Ok(()) => {
+                        self.link.send_message(WorkFunction::FetchOwnedBadges);
+                    }
+                    Err(err) => {
+                        warn!("{:?}", err);
+                        self.claim_error = Some(err.report());
+                        self.status = WorkFunction::Done;
+                    }
+                }
+                true
+            }
+            WorkFunction::ModalRevokeChange(proof) => {
+                self.revoking_proof = proof;
+                false
+            }
+            WorkFunction::RevokeProof => {
+                let pub_key = self.props.account.clone();
+                let proof = self.revoking_proof.clone();
+                self.revoke_result = None;
+                self.link.send_future(async move {
+                    WorkFunction::RevokeDone(revoke_proof(&pub_key, &proof).await)
+                });
+                self.modal_shown = false;
+                self.status = WorkFunction::Done;
+                true
+            }
+            WorkFunction::RevokeDone(response) => {
+                if let Err(err) = &response {
+                    warn!("{:?}", err);
+                }
+                self.revoke_result = Some(response);
+                self.status = WorkFunction::Done;
+                true
+            }
             WorkFunction::None => false,
             WorkFunction::Err(_) => true,
         }
@@ -236,28 +420,168 @@ impl Component for AccountView {
 
     fn view(&self) -> yew::Html {
         match self.status.clone() {
-            WorkFunction::Err(msg) => self.view_err(&msg),
+            WorkFunction::Err(err) => self.view_err(&err),
             WorkFunction::Done | WorkFunction::ProofSignDone(_) => self.view_account(),
             other => self.view_loading(other),
         }
     }
 }
 
-fn render_series(series: &String, badges: &Vec<Badge>) -> Html {
+fn render_series(
+    group: &String,
+    series: Option<&Series>,
+    badges: &Vec<Badge>,
+    on_claim: &Callback<Badge>,
+) -> Html {
     html! {
-        <section class="section">
-        <h1 class="title" style="text-align: center">{series}</h1>
+        <section class={series_classes(series)}>
+        { view_series_title(group, series) }
         {
             badges.clone().into_iter()
-            .filter(|b| b.owned)
+            .filter(|b| b.owned || b.pending)
             .chain(badges.clone().into_iter().filter(|b| !b.is_mono()))
             .unique_by(|b| b.token.code.clone())
             .sorted_by(|a, b| a.token.code.cmp(&b.token.code))
+            .map(|b| html! {
+                <BadgeCard badge={b} valid={true} on_claim={Some(on_claim.clone())}/>
+            })
             .collect::<Html>()}
         </section>
     }
 }
 
+async fn build_claim_transaction(pub_key: &String, badge: &Badge) -> Result<String, Error> {
+    let balance_id = badge
+        .claimable_balance_id
+        .clone()
+        .ok_or(Error::Other(format!(
+            "{} is not claimable",
+            badge.token.code
+        )))?;
+    let account = stellar::fetch_account(pub_key).await?;
+    let has_trustline = account
+        .balances
+        .iter()
+        .any(|bal| bal.asset_code == badge.token.code && bal.asset_issuer == badge.token.issuer);
+
+    transaction::claim_claimable_balance(
+        pub_key,
+        &account.sequence,
+        &balance_id,
+        &badge.token.code,
+        &badge.token.issuer,
+        !has_trustline,
+    )
+}
+
+/// Adds the badges of all `cosigners` except the `undisclosed` ones to the proof
+/// and signs it with every account, the connected wallet is asked once per account
+async fn sign_proof(
+    pub_key: &String,
+    cosigners: &[String],
+    undisclosed: &HashSet<String>,
+    mut proof: Proof,
+    available_badges: &[TOMLCurrency],
+) -> Result<SignedMessage, Error> {
+    for cosigner in cosigners {
+        let badges = badge_check::fetch_badges(cosigner, available_badges)
+            .await
+            .map_err(|err| err.context(format!("The account {} could not be checked", cosigner)))?;
+        proof.owned_badges.extend(
+            badges
+                .into_iter()
+                .filter(|b| b.owned && !undisclosed.contains(&b.token.code))
+                .map(|b| b.token),
+        );
+    }
+    proof.owned_badges = proof
+        .owned_badges
+        .into_iter()
+        .unique_by(|t| t.asset())
+        .collect();
+    let data = proof.encode()?;
+    // every account signs the set of accounts along with the proof
+    let accounts = std::iter::once(pub_key.clone())
+        .chain(cosigners.iter().cloned())
+        .collect::<Vec<String>>();
+    let text = proof_encoding::signed_text(&data, &accounts);
+
+    let provider = wallet::connected().provider();
+    let mut signed = provider.sign_message(pub_key, &text).await?;
+    signed.message = data;
+    for cosigner in cosigners {
+        let cosigned = provider
+            .sign_message(cosigner, &text)
+            .await
+            .map_err(|err| err.context(format!("Signing with {}", cosigner)))?;
+        if &cosigned.pubkey != cosigner || cosigned.message != text {
+            return Err(Error::Other(format!(
+                "The wallet did not sign with {}!",
+                cosigner
+            )));
+        }
+        signed.cosigners.push(Cosignature {
+            scheme: cosigned.scheme,
+            pubkey: cosigned.pubkey,
+            signature: cosigned.signature,
+        });
+    }
+    Ok(signed)
+}
+
+/// Publishes the revocation of a proof signed by `pub_key`, returns the id of the proof
+async fn revoke_proof(pub_key: &String, proof: &String) -> Result<String, Error> {
+    let signed = SignedMessage::parse(proof).map_err(|err| err.context("Reading the proof"))?;
+    if !signed.accounts().contains(pub_key) {
+        return Err(Error::Other(String::from(
+            "The proof was not signed by this account!",
+        )));
+    }
+    let account = stellar::fetch_account(pub_key).await?;
+    let xdr = revocation::revoke_transaction(pub_key, &account.sequence, &signed.message)?;
+    wallet::connected()
+        .provider()
+        .sign_transaction(pub_key, &xdr)
+        .await
+        .map_err(|err| err.context("Publishing the revocation"))?;
+
+    Ok(revocation::proof_id(&signed.message))
+}
+
+/// Renders the badge wall with the badge images embedded, so that it displays on its own.
+/// Images that can't be fetched are referenced by url instead.
+async fn render_badge_wall(account: &str, badges: &[Badge]) -> String {
+    let mut images = HashMap::new();
+    for badge in badge_wall::wall_badges(badges) {
+        let url = &badge.token.image;
+        match fetch::get_bytes(url).await {
+            Ok(bytes) => {
+                images.insert(
+                    url.clone(),
+                    svg::data_url(badge_wall::image_mime(url), &bytes),
+                );
+            }
+            Err(err) => warn!("Embedding {}: {:?}", url, err),
+        }
+    }
+    badge_wall::render(account, badges, &images)
+}
+
+/// Submits a transaction committing to the signed proof and records its hash in the proof
+async fn anchor_proof(pub_key: &String, mut signed: SignedMessage) -> Result<SignedMessage, Error> {
+    let account = stellar::fetch_account(pub_key).await?;
+    let xdr = anchor::anchor_transaction(pub_key, &account.sequence, &signed.message)?;
+    let tx_hash = transaction::transaction_hash(&xdr, transaction::PUBLIC_NETWORK_PASSPHRASE)?;
+    wallet::connected()
+        .provider()
+        .sign_transaction(pub_key, &xdr)
+        .await
+        .map_err(|err| err.context("Anchoring the proof on-chain"))?;
+
+    signed.anchor = Some(tx_hash);
+    Ok(signed)
+}
+
 impl AccountView {
     fn view_account(&self) -> Html {
         let owned_num = self
@@ -277,6 +601,15 @@ impl AccountView {
             .filter(|b| b.owned)
             .unique_by(|b| b.token.code.clone())
             .count();
+        let held_num = self
+            .storage
+            .owned_badges
+            .clone()
+            .unwrap_or(vec![])
+            .into_iter()
+            .filter(|b| b.held)
+            .unique_by(|b| b.token.code.clone())
+            .count();
         let badges_num = self
             .storage
             .owned_badges
@@ -286,6 +619,8 @@ impl AccountView {
             .unique_by(|b| b.token.code.clone())
             .count();
 
+        let on_claim = self.link.callback(WorkFunction::ClaimBadge);
+
         html! {
             <>
                 <h2 class="title mid-center" style="text-align: center">
@@ -306,7 +641,41 @@ impl AccountView {
                             "".to_string()
                         }
                     }
+                    {
+                        if held_num != completed_num {
+                            format!(", currently holding {}", held_num)
+                        } else {
+                            "".to_string()
+                        }
+                    }
+                    {" "}
+                    { go_to(Route::Compare { ids: self.props.account.clone() }, html! { {"Compare with other accounts"} }, vec![]) }
+                    {" · "}
+                    <a onclick={self.link.callback(|_| WorkFunction::ToggleTimeline)}>
+                        { if self.show_timeline { "Show by series" } else { "Show timeline" } }
+                    </a>
+                    {" · "}
+                    <a onclick={self.link.callback(|_| WorkFunction::RenderBadgeWall)}>{"Share as image"}</a>
+                    {" · "}
+                    <a onclick={self.link.callback(|_| WorkFunction::ToggleEmbed)}>{"Embed"}</a>
                 </p>
+                { self.view_badge_wall() }
+                { self.view_embed() }
+                {
+                    if let Some(err) = self.claim_error.clone() {
+                        html! {
+                            <article class="message mid-center is-danger" style="margin-top: 1.5rem; margin-bottom: 0">
+                                <div class="message-header">
+                                    <p>{"Claiming Error"}</p>
+                                </div>
+                                <div class="message-body">{err}</div>
+                            </article>
+                        }
+                    } else {
+                        Html::default()
+                    }
+                }
+                { self.view_revoke_result() }
                 {
                     if let WorkFunction::ProofSignDone(_) = self.status.clone() {
                         self.view_proof_sign_response()
@@ -314,23 +683,13 @@ impl AccountView {
                         "".to_string().into()
                     }
                 }
-                <div class="badges">
                 {
-                    self.storage.owned_badges.clone()
-                        .unwrap_or(vec![]).into_iter()
-                        .group_by(|badge| {
-                            let mut series = badge.token.code.clone();
-                            if series.starts_with("SSQ") {
-                                series.truncate(3);
-                            } else {
-                                series.truncate(4);
-                            }
-                            series
-                        }).into_iter()
-                        .map(|(series, badges)|render_series(&series, &badges.collect()))
-                        .collect::<Html>()
+                    if self.show_timeline {
+                        html! { <Timeline badges={self.storage.owned_badges.clone().unwrap_or_default()}/> }
+                    } else {
+                        self.view_series(&on_claim)
+                    }
                 }
-                </div>
                 <button onclick={self.link.callback(|_| WorkFunction::ToggleModal)} class="button is-floating is-primary">
                     <i class="fas fa-key"></i>
                 </button>
@@ -345,6 +704,101 @@ impl AccountView {
         }
     }
 
+    fn view_series(&self, on_claim: &Callback<Badge>) -> Html {
+        let badges = self.storage.owned_badges.clone().unwrap_or_default();
+        let progress = series::progress(
+            &self.storage.available_badges.clone().unwrap_or_default(),
+            &badges,
+            Badge::is_earned,
+        );
+        html! {
+            <div class="badges">
+            {
+                badges.into_iter()
+                    .sorted_by_key(|badge| series::group_key(&badge.token.code))
+                    .group_by(|badge| series::group_key(&badge.token.code))
+                    .into_iter()
+                    .map(|(group, badges)| {
+                        let series = progress.iter().find(|s| s.group() == group);
+                        render_series(&group, series, &badges.collect(), on_claim)
+                    })
+                    .collect::<Html>()
+            }
+            </div>
+        }
+    }
+
+    fn view_badge_wall(&self) -> Html {
+        let wall = match &self.badge_wall {
+            Some(wall) => svg::data_url("image/svg+xml", wall.as_bytes()),
+            None => return Html::default(),
+        };
+        html! {
+            <article class="message mid-center" style="margin-top: 1.5rem; margin-bottom: 0">
+                <div class="message-header">
+                    <p>{"Badge wall"}</p>
+                    <button class="delete" aria-label="close" onclick={self.link.callback(|_| WorkFunction::CloseBadgeWall)}></button>
+                </div>
+                <div class="message-body" style="text-align: center">
+                    <img style="display: block; margin: 0 auto 1rem; max-width: 100%" src={wall.clone()} alt="Owned badges"/>
+                    <a class="button is-primary" href={wall} download={format!("badges-{}.svg", self.props.account)}>
+                        {"Download SVG"}
+                    </a>
+                </div>
+            </article>
+        }
+    }
+
+    fn view_embed(&self) -> Html {
+        if !self.show_embed {
+            return Html::default();
+        }
+        let base = app_base();
+        let options = EmbedOptions::default();
+        html! {
+            <article class="message mid-center" style="margin-top: 1.5rem; margin-bottom: 0">
+                <div class="message-header">
+                    <p>{"Embed the badges in another site"}</p>
+                    <button class="delete" aria-label="close" onclick={self.link.callback(|_| WorkFunction::ToggleEmbed)}></button>
+                </div>
+                <div class="message-body">
+                    <p>{"As an iframe:"}</p>
+                    <textarea class="textarea is-family-monospace is-small mb-3" readonly={true} rows="3"
+                              value={embed::iframe_snippet(&base, &self.props.account, &options)}/>
+                    <p>{"As a web component:"}</p>
+                    <textarea class="textarea is-family-monospace is-small mb-3" readonly={true} rows="3"
+                              value={embed::component_snippet(&base, &self.props.account, &options)}/>
+                    <p class="is-size-7">
+                        {"Options: "}<code>{"size=small|medium|large"}</code>{", "}<code>{"series=1,2"}</code>
+                        {", "}<code>{"theme=light|dark"}</code>{" and "}<code>{"missing=false"}</code>
+                        {" as query parameters or attributes. "}
+                        <a href={embed::embed_url(&base, &self.props.account, &options)} target="_blank">{"Preview"}</a>
+                    </p>
+                </div>
+            </article>
+        }
+    }
+
+    fn view_revoke_result(&self) -> Html {
+        let (class, header, message) = match &self.revoke_result {
+            Some(Ok(id)) => (
+                "is-success",
+                "Proof revoked",
+                format!("The proof {} is revoked from now on.", id),
+            ),
+            Some(Err(err)) => ("is-danger", "Revocation Error", err.report()),
+            None => return Html::default(),
+        };
+        html! {
+            <article class={classes!("message", "mid-center", class)} style="margin-top: 1.5rem; margin-bottom: 0">
+                <div class="message-header">
+                    <p>{header}</p>
+                </div>
+                <div class="message-body" style="word-break: break-all;">{message}</div>
+            </article>
+        }
+    }
+
     fn view_proof_sign_response(&self) -> Html {
         if let WorkFunction::ProofSignDone(response) = self.status.clone() {
             let message: String;
@@ -352,63 +806,20 @@ impl AccountView {
             let class: String;
 
             match response {
-                Ok(resp) => {
-                    let mut message_opt: Option<String> = None;
-                    let mut message_class_opt: Option<String> = None;
-                    let mut message_header_opt: Option<String> = None;
-                    if let Ok(resp) = resp.into_serde::<Value>() {
-                        let resp = resp.as_object().unwrap(); // todo make safe again
-
-                        if resp.get("intent").unwrap().as_str() == Some("sign_message") {
-                            let _included_message = resp.get("message").unwrap().as_str().unwrap();
-                            let message_signature =
-                                resp.get("message_signature").unwrap().as_str().unwrap();
-                            let pub_key = resp.get("pubkey").unwrap().as_str().unwrap();
-                            let signed_message =
-                                resp.get("signed_message").unwrap().as_str().unwrap();
-
-                            if pub_key == self.props.account {
-                                message_header_opt = Some(String::from("Success"));
-                                message_class_opt = Some("is-success".to_string());
-
-                                let interesting =
-                                    format!("{}:{}", message_signature, signed_message);
-
-                                message_opt = Some(base64::encode(interesting));
-                            } else {
-                                message_opt = Some("Signing key does not match!".to_string());
-                            }
-                        } else {
-                            message_opt = Some("Wrong intent!".to_string());
-                        }
-                    }
-
-                    class = message_class_opt.unwrap_or("is-danger".to_string());
-                    message_header = message_header_opt.unwrap_or("Error".to_string());
-                    message = message_opt.unwrap_or("unknown".to_string());
+                Ok(signed) if signed.pubkey == self.props.account => {
+                    message_header = String::from("Success");
+                    class = "is-success".to_string();
+                    message = signed.encode();
+                }
+                Ok(_) => {
+                    message_header = String::from("Error");
+                    class = "is-danger".to_string();
+                    message = "Signing key does not match!".to_string();
                 }
                 Err(err) => {
-                    let err: serde_json::Result<serde_json::Value> = err.into_serde();
-
-                    let get_err =
-                        |err: serde_json::Result<serde_json::Value>| -> serde_json::Result<String> {
-                            let err = err?;
-
-                            let err = err.as_object().unwrap(); // todo make safe again
-
-                            let err_msg = err.get("message").unwrap().as_str().unwrap();
-
-                            Ok(format!("Albedo: {}", err_msg))
-                        };
-
-                    if let Ok(err) = get_err(err) {
-                        message = err;
-                    } else {
-                        message = "unknown".to_string();
-                    }
-
                     message_header = String::from("Signing Error");
                     class = "is-danger".to_string();
+                    message = err.report();
                 }
             };
 
@@ -452,9 +863,9 @@ impl AccountView {
             </div>
         }
     }
-    fn view_err(&self, message: &String) -> Html {
+    fn view_err(&self, err: &Error) -> Html {
         html! {
-            <ErrorCard message={message.clone()} />
+            <ErrorCard message={err.report()} code={Some(err.code())} />
         }
     }
     fn render_modal(&self) -> Html {
@@ -467,10 +878,91 @@ impl AccountView {
         }
     }
 
+    /// Codes of all owned badges, sorted
+    fn owned_codes(&self) -> Vec<String> {
+        self.storage
+            .owned_badges
+            .clone()
+            .unwrap_or(vec![])
+            .into_iter()
+            .filter(|b| b.owned)
+            .map(|b| b.token.code)
+            .unique()
+            .sorted()
+            .collect()
+    }
+
+    /// Picker for the badges to include and the predicates to claim instead
+    fn render_disclosure(&self) -> Html {
+        let owned_badges = self.storage.owned_badges.clone().unwrap_or(vec![]);
+        let series = self
+            .owned_codes()
+            .into_iter()
+            .sorted_by_key(|code| series_of(code))
+            .group_by(|code| series_of(code))
+            .into_iter()
+            .filter_map(|(series, codes)| series.map(|s| (s, codes.collect::<Vec<String>>())))
+            .collect::<Vec<(usize, Vec<String>)>>();
+
+        let complete_series = series
+            .iter()
+            .map(|(series, _)| Predicate::CompleteSeries(*series))
+            .filter(|p| badge_check::predicate_met(p, &owned_badges, &OwnershipRequirement::Earned))
+            .collect::<Vec<Predicate>>();
+        let min_badges_change = self
+            .link
+            .callback(|e: InputData| WorkFunction::ModalMinBadgesChange(e.value));
+
+        html! {
+            <>
+                <p class="mt-1 mb-1">{"Badges included in the proof:"}</p>
+                {
+                    series.into_iter().map(|(series, codes)| html! {
+                        <div class="tags">
+                            <a class="tag is-dark" onclick={self.link.callback(move |_| WorkFunction::ToggleSeriesDisclosed(series))}>
+                                {format!("Series {}", series)}
+                            </a>
+                            {
+                                codes.into_iter().map(|code| {
+                                    let class = match self.undisclosed.contains(&code) {
+                                        true => "tag",
+                                        false => "tag is-primary",
+                                    };
+                                    let toggled = code.clone();
+                                    html! {
+                                        <a class={class} onclick={self.link.callback(move |_| WorkFunction::ToggleDisclosed(toggled.clone()))}>
+                                            {code}
+                                        </a>
+                                    }
+                                }).collect::<Html>()
+                            }
+                        </div>
+                    }).collect::<Html>()
+                }
+                {
+                    complete_series.into_iter().map(|predicate| html! {
+                        <label class="checkbox mr-3">
+                            <input type="checkbox" checked={self.predicates.contains(&predicate)}
+                             onclick={self.link.callback(move |_| WorkFunction::TogglePredicate(predicate))}/>
+                            {format!(" Claim: {}", predicate.describe())}
+                        </label>
+                    }).collect::<Html>()
+                }
+                <input class="input mt-1" type="number" min="1" placeholder="Claim to own at least this many badges" oninput={min_badges_change}/>
+            </>
+        }
+    }
+
     fn render_modal_content(&self) -> Html {
         let proof_text_change = self
             .link
             .callback(|e: InputData| WorkFunction::ModalProofTextChange(e.value));
+        let cosigners_change = self
+            .link
+            .callback(|e: InputData| WorkFunction::ModalCosignersChange(e.value));
+        let revoke_change = self
+            .link
+            .callback(|e: InputData| WorkFunction::ModalRevokeChange(e.value));
         html! {
             <div class="card">
                 <div class="card-content">
@@ -478,9 +970,21 @@ impl AccountView {
 
                         <h1 class="title is-centered" style="text-align: center">{"Specify proof message."}</h1>
                         <textarea class="textarea" placeholder="Enter message..." name="proof" oninput={proof_text_change}/>
+                        <label class="checkbox mt-1">
+                            <input type="checkbox" checked={self.anchor_proof} onclick={self.link.callback(|_| WorkFunction::ToggleAnchor)}/>
+                            {" Anchor on-chain (submits a transaction without effect, costs the base fee)"}
+                        </label>
+                        <input class="input mt-1" type="text" placeholder="Further accounts of yours to include (G..., comma separated)" oninput={cosigners_change}/>
+                        { self.render_disclosure() }
                         <div class="mt-1" style="display: flex; justify-content: flex-end">
                             <button class="button is-primary" onclick={self.link.callback(|_| WorkFunction::CreateProof)}>{"Sign"}</button>
                         </div>
+
+                        <h2 class="subtitle mt-3">{"Revoke a leaked proof"}</h2>
+                        <input class="input" type="text" placeholder="Paste the proof..." oninput={revoke_change}/>
+                        <div class="mt-1" style="display: flex; justify-content: flex-end">
+                            <button class="button is-danger" onclick={self.link.callback(|_| WorkFunction::RevokeProof)}>{"Revoke"}</button>
+                        </div>
                     </div>
                 </div>
             </div>
@@ -488,6 +992,11 @@ impl AccountView {
     }
 }
 
+/// Series number of a badge code, `SSQxx` belonging to series xx
+fn series_of(code: &str) -> Option<usize> {
+    proof_encoding::quest_position(code).map(|(series, _)| series)
+}
+
 fn check_valid_public_key(_: &String) -> bool {
     true
 }
@@ -0,0 +1,436 @@
+use log::{debug, warn};
+use yew::prelude::*;
+use yew::{html, Component, ComponentLink};
+
+use crate::stellar::stellar_data::{Operation, OperationPayment, Transaction};
+use crate::stellar::*;
+use crate::util::badge_check::{self, Badge, Provenance};
+use crate::util::error::Error;
+use crate::webpage::components::error::ErrorCard;
+use crate::webpage::view::{go_to, Route};
+
+#[derive(Clone, Debug, Eq, PartialEq, Properties)]
+pub struct Props {
+    pub account: String,
+    pub code: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct BadgeStorage {
+    pub badge: Option<Badge>,
+    /// All fields of the badges `[[CURRENCIES]]` entry
+    pub metadata: Vec<(String, String)>,
+    /// The transaction the badge was acquired in
+    pub transaction: Option<Transaction>,
+    pub operations: Vec<Operation>,
+    /// Payments of the badge from and to the account, oldest first
+    pub payments: Vec<OperationPayment>,
+    /// Why the history could not be loaded, the badge is still shown without it
+    pub history_error: Option<Error>,
+}
+
+pub struct BadgeDetail {
+    link: ComponentLink<BadgeDetail>,
+    props: Props,
+    status: LoadStatus,
+    storage: BadgeStorage,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum LoadStatus {
+    Begin,
+    FetchBadge,
+    FetchBadgeDone {
+        badge: Badge,
+        metadata: Vec<(String, String)>,
+    },
+    FetchHistory,
+    FetchHistoryDone {
+        transaction: Option<Transaction>,
+        operations: Vec<Operation>,
+        payments: Vec<OperationPayment>,
+    },
+    FetchHistoryFailed(Error),
+    Done,
+    Err(Error),
+}
+
+/// One step in the history of the badge in the account
+struct TimelineEntry {
+    date: String,
+    description: String,
+    tx_hash: String,
+}
+
+impl Component for BadgeDetail {
+    type Message = LoadStatus;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            link: link,
+            props: props,
+            status: LoadStatus::Begin,
+            storage: BadgeStorage::default(),
+        }
+    }
+
+    fn rendered(&mut self, first_render: bool) {
+        if first_render {
+            self.link.send_message(LoadStatus::Begin);
+        }
+    }
+
+    fn update(&mut self, status: Self::Message) -> yew::ShouldRender {
+        self.status = status.clone();
+        debug!("LoadStatus: {:?}", status);
+        match status {
+            LoadStatus::Begin => {
+                self.link.send_message(LoadStatus::FetchBadge);
+                false
+            }
+            LoadStatus::FetchBadge => {
+                let account = self.props.account.clone();
+                let code = self.props.code.clone();
+                self.link.send_future(async move {
+                    match fetch_badge(&account, &code).await {
+                        Ok((badge, metadata)) => LoadStatus::FetchBadgeDone { badge, metadata },
+                        Err(err) => LoadStatus::Err(err),
+                    }
+                });
+                false
+            }
+            LoadStatus::FetchBadgeDone { badge, metadata } => {
+                self.storage.badge = Some(badge);
+                self.storage.metadata = metadata;
+                self.link.send_message(LoadStatus::FetchHistory);
+                false
+            }
+            LoadStatus::FetchHistory => {
+                let account = self.props.account.clone();
+                let badge = self.storage.badge.clone().unwrap_or_default();
+                self.link.send_future(async move {
+                    match fetch_history(&account, &badge).await {
+                        Ok((transaction, operations, payments)) => LoadStatus::FetchHistoryDone {
+                            transaction,
+                            operations,
+                            payments,
+                        },
+                        Err(err) => LoadStatus::FetchHistoryFailed(err.context(format!(
+                            "The history of {} could not be loaded",
+                            badge.token.code
+                        ))),
+                    }
+                });
+                false
+            }
+            LoadStatus::FetchHistoryDone {
+                transaction,
+                operations,
+                payments,
+            } => {
+                self.storage.transaction = transaction;
+                self.storage.operations = operations;
+                self.storage.payments = payments;
+                self.link.send_message(LoadStatus::Done);
+                false
+            }
+            LoadStatus::FetchHistoryFailed(err) => {
+                warn!("{}", err.report());
+                self.storage.history_error = Some(err);
+                self.link.send_message(LoadStatus::Done);
+                false
+            }
+            LoadStatus::Done => {
+                debug!("{:?}", self.storage);
+                true
+            }
+            LoadStatus::Err(_) => true,
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> yew::ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        self.storage = BadgeStorage::default();
+        self.link.send_message(LoadStatus::Begin);
+        false
+    }
+
+    fn view(&self) -> yew::Html {
+        match self.status.clone() {
+            LoadStatus::Err(err) => html! {
+                <ErrorCard message={err.report()} code={Some(err.code())}/>
+            },
+            LoadStatus::Done => self.view_badge(),
+            other => self.view_loading(other),
+        }
+    }
+}
+
+/// Resolves the badge `code` of `account`, preferring an owned variant over the mono one,
+/// together with its TOML metadata
+async fn fetch_badge(account: &str, code: &str) -> Result<(Badge, Vec<(String, String)>), Error> {
+    let toml_url = String::from("https://quest.stellar.org/.well-known/stellar.toml");
+    let currencies = stellar::fetch_toml_currencies(&toml_url)
+        .await
+        .ok_or(Error::Other(String::from(
+            "The available badges could not be loaded from quest.stellar.org!",
+        )))?
+        .into_iter()
+        .filter(|c| c.code == code)
+        .collect::<Vec<_>>();
+    if currencies.is_empty() {
+        return Err(Error::Other(format!("{} is not a known badge!", code)));
+    }
+
+    let badges = badge_check::fetch_badges(account, &currencies).await?;
+    let badge = badges
+        .iter()
+        .find(|b| b.owned)
+        .or(badges.first())
+        .cloned()
+        .unwrap_or_default();
+    let metadata =
+        stellar::fetch_toml_currency_metadata(&toml_url, &badge.token.code, &badge.token.issuer)
+            .await
+            .unwrap_or_else(|| {
+                warn!("No TOML metadata for {}", badge.token.asset());
+                vec![]
+            });
+    Ok((badge, metadata))
+}
+
+/// Loads the acquisition transaction and all payments of the badge involving `account`
+///
+/// The payments are only scanned back to the creation of the trustline, so an account that
+/// no longer trusts the badge gets none and the timeline falls back to the acquisition.
+async fn fetch_history(
+    account: &str,
+    badge: &Badge,
+) -> Result<(Option<Transaction>, Vec<Operation>, Vec<OperationPayment>), Error> {
+    let trusted = stellar::fetch_account(account)
+        .await?
+        .balances
+        .iter()
+        .any(|b| b.asset_code == badge.token.code && b.asset_issuer == badge.token.issuer);
+    let assets = vec![badge.token.asset()];
+    let payments = if trusted {
+        stellar::fetch_account_payments_for_assets(account, &assets, &assets)
+    } else {
+        stellar::fetch_account_payments_for_assets(account, &[], &assets)
+    };
+    let (transaction, operations) = match &badge.tx_hash {
+        Some(hash) => {
+            let (transaction, operations) = futures::try_join!(
+                stellar::fetch_transaction(hash),
+                stellar::fetch_transaction_operations(hash)
+            )?;
+            (Some(transaction), operations)
+        }
+        None => (None, vec![]),
+    };
+    Ok((transaction, operations, payments.await?))
+}
+
+impl BadgeDetail {
+    fn view_badge(&self) -> Html {
+        let badge = self.storage.badge.clone().unwrap_or_default();
+        let status = match (badge.owned, badge.held, badge.pending) {
+            (true, true, _) => String::from("Owned and held"),
+            (true, false, _) => String::from("Owned, but no longer held"),
+            (false, _, true) => String::from("Ready to be claimed"),
+            _ => String::from("Not acquired yet"),
+        };
+        html! {
+            <div class="container is-max-desktop">
+                <section class="section">
+                    <div style="text-align: center">
+                        <img style="width: 8em; height: 8em" src={badge.token.image.clone()} alt="" />
+                        <h1 class="title">{&badge.token.code}</h1>
+                        <p class="subtitle">
+                            {status}
+                            {
+                                match &badge.provenance {
+                                    Some(provenance) => format!(" ({})", provenance.describe()),
+                                    None => String::default(),
+                                }
+                            }
+                        </p>
+                        <p>
+                            {"in account "}
+                            { go_to(Route::Account { id: self.props.account.clone() }, html! { {&self.props.account} }, vec![]) }
+                        </p>
+                    </div>
+                </section>
+                { self.view_history_error() }
+                { self.view_acquisition() }
+                { self.view_timeline(&badge) }
+                { self.view_metadata() }
+            </div>
+        }
+    }
+
+    fn view_history_error(&self) -> Html {
+        match &self.storage.history_error {
+            Some(err) => html! {
+                <section class="section">
+                    <div class="notification is-warning">
+                        {format!("History unavailable: {}", err.report())}
+                    </div>
+                </section>
+            },
+            None => Html::default(),
+        }
+    }
+
+    fn view_acquisition(&self) -> Html {
+        let transaction = match &self.storage.transaction {
+            Some(transaction) => transaction,
+            None => return Html::default(),
+        };
+        let memo = match &transaction.memo {
+            Some(memo) => format!("{} ({})", memo, transaction.memo_type),
+            None => transaction.memo_type.clone(),
+        };
+        let operations = self
+            .storage
+            .operations
+            .iter()
+            .map(|o| o.type_name.clone())
+            .collect::<Vec<String>>()
+            .join(", ");
+        html! {
+            <section class="section">
+                <h2 class="title is-4">{"Acquisition"}</h2>
+                <table class="table is-fullwidth">
+                    <tbody>
+                        <tr>
+                            <th>{"Transaction"}</th>
+                            <td style="word-break: break-all">
+                                <a href={format!("https://stellar.expert/explorer/public/tx/{}", transaction.hash)} target="_blank">{&transaction.hash}</a>
+                            </td>
+                        </tr>
+                        <tr><th>{"Ledger"}</th><td>{transaction.ledger}</td></tr>
+                        <tr><th>{"Closed at"}</th><td>{&transaction.created_at}</td></tr>
+                        <tr><th>{"Memo"}</th><td style="word-break: break-all">{memo}</td></tr>
+                        <tr><th>{"Fee charged"}</th><td>{format!("{} stroops", transaction.fee_charged)}</td></tr>
+                        <tr><th>{"Operations"}</th><td>{operations}</td></tr>
+                    </tbody>
+                </table>
+            </section>
+        }
+    }
+
+    fn view_timeline(&self, badge: &Badge) -> Html {
+        let entries = timeline(&self.props.account, badge, &self.storage.payments);
+        if entries.is_empty() {
+            return Html::default();
+        }
+        html! {
+            <section class="section">
+                <h2 class="title is-4">{"Provenance"}</h2>
+                <ul>
+                {
+                    entries.into_iter().map(|entry| html! {
+                        <li>
+                            <strong>{entry.date}</strong>
+                            {format!(" {} ", entry.description)}
+                            <a href={format!("https://stellar.expert/explorer/public/tx/{}", entry.tx_hash)} target="_blank">
+                                <i class="fas fa-external-link-alt"></i>
+                            </a>
+                        </li>
+                    }).collect::<Html>()
+                }
+                </ul>
+            </section>
+        }
+    }
+
+    fn view_metadata(&self) -> Html {
+        if self.storage.metadata.is_empty() {
+            return Html::default();
+        }
+        html! {
+            <section class="section">
+                <h2 class="title is-4">{"stellar.toml"}</h2>
+                <table class="table is-fullwidth">
+                    <tbody>
+                    {
+                        self.storage.metadata.iter().map(|(key, value)| html! {
+                            <tr><th>{key}</th><td style="word-break: break-all">{value}</td></tr>
+                        }).collect::<Html>()
+                    }
+                    </tbody>
+                </table>
+            </section>
+        }
+    }
+
+    fn view_loading(&self, status: LoadStatus) -> Html {
+        let description = match status {
+            LoadStatus::Begin | LoadStatus::FetchBadge | LoadStatus::FetchBadgeDone { .. } => {
+                String::from("Verifying the badge...")
+            }
+            LoadStatus::FetchHistory
+            | LoadStatus::FetchHistoryDone { .. }
+            | LoadStatus::FetchHistoryFailed(_) => {
+                String::from("Fetching the history of the badge...")
+            }
+            _ => String::default(),
+        };
+        html! {
+            <div class="container is-max-desktop">
+                <div class="sqb-centered">
+                    <h2 class="subtitle is-centered">{"Loading "}{&self.props.code}{" of "} <i>{&self.props.account}</i></h2>
+                    {description}
+                </div>
+            </div>
+        }
+    }
+}
+
+/// Orders the payments of the badge and its claim from a claimable balance by date
+fn timeline(account: &str, badge: &Badge, payments: &[OperationPayment]) -> Vec<TimelineEntry> {
+    let mut entries = payments
+        .iter()
+        .map(|p| {
+            let description = if p.to == account && p.from == p.asset_issuer {
+                String::from("paid out by the issuer")
+            } else if p.to == account {
+                format!("received from {}", p.from)
+            } else {
+                format!("sent to {}", p.to)
+            };
+            TimelineEntry {
+                date: p.created_at.clone(),
+                description,
+                tx_hash: p.transaction_hash.clone(),
+            }
+        })
+        .collect::<Vec<TimelineEntry>>();
+
+    // claims are no payments, and the payments of an untrusted badge are not scanned
+    if let (Some(provenance), Some(date), Some(tx_hash)) =
+        (&badge.provenance, &badge.date_accuired, &badge.tx_hash)
+    {
+        if !entries.iter().any(|e| &e.tx_hash == tx_hash) {
+            let description = match provenance {
+                Provenance::IssuerClaimableBalance => {
+                    String::from("claimed from a claimable balance of the issuer")
+                }
+                other => other.describe(),
+            };
+            entries.push(TimelineEntry {
+                date: date.clone(),
+                description,
+                tx_hash: tx_hash.clone(),
+            });
+        }
+    }
+    // horizon dates are ISO 8601 in UTC, so they sort lexicographically
+    entries.sort_by(|a, b| a.date.cmp(&b.date));
+    entries
+}
@@ -0,0 +1,378 @@
+use futures::StreamExt;
+use itertools::Itertools;
+use log::debug;
+use yew::prelude::*;
+use yew::{html, Component, ComponentLink};
+
+use crate::stellar::stellar_data::TOMLCurrency;
+use crate::stellar::*;
+use crate::util::badge_check::{self, Badge, ResolveOptions};
+use crate::util::error::Error;
+use crate::util::proof_encoding;
+use crate::webpage::components::badge::short_account;
+use crate::webpage::components::error::ErrorCard;
+use crate::webpage::view::{go_to, Route};
+
+#[derive(Clone, Debug, Eq, PartialEq, Properties)]
+pub struct Props {
+    /// Comma separated account ids
+    pub ids: String,
+}
+
+pub struct CompareView {
+    link: ComponentLink<CompareView>,
+    props: Props,
+    status: WorkFunction,
+    available_badges: Vec<TOMLCurrency>,
+    /// Resolved badges per compared account, in the order of the route
+    accounts: Vec<(String, Result<Vec<Badge>, Error>)>,
+    new_account: String,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum WorkFunction {
+    Begin,
+    FetchAvailableBadges,
+    FetchAvailableBadgesDone {
+        available_badges: Vec<TOMLCurrency>,
+    },
+    FetchBadges,
+    FetchBadgesDone {
+        accounts: Vec<(String, Result<Vec<Badge>, Error>)>,
+    },
+    NewAccountChange(String),
+    AddAccount,
+    RemoveAccount(String),
+    Done,
+    Err(Error),
+}
+
+impl Component for CompareView {
+    type Message = WorkFunction;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            link: link,
+            props: props,
+            status: WorkFunction::Begin,
+            available_badges: vec![],
+            accounts: vec![],
+            new_account: String::new(),
+        }
+    }
+
+    fn rendered(&mut self, first_render: bool) {
+        if first_render {
+            self.link.send_message(WorkFunction::Begin);
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> yew::ShouldRender {
+        debug!("WorkFunction: {:?}", msg);
+        match msg {
+            WorkFunction::NewAccountChange(account) => {
+                self.new_account = account;
+                return false;
+            }
+            WorkFunction::AddAccount => {
+                let mut ids = parse_ids(&self.props.ids);
+                ids.push(self.new_account.trim().to_string());
+                self.new_account = String::new();
+                yew_router::push_route(Route::Compare {
+                    ids: ids.into_iter().unique().join(","),
+                });
+                return false;
+            }
+            WorkFunction::RemoveAccount(account) => {
+                let ids = parse_ids(&self.props.ids)
+                    .into_iter()
+                    .filter(|id| id != &account)
+                    .join(",");
+                yew_router::push_route(Route::Compare { ids });
+                return false;
+            }
+            _ => {}
+        }
+
+        self.status = msg.clone();
+        match msg {
+            WorkFunction::Begin => {
+                self.link.send_message(WorkFunction::FetchAvailableBadges);
+                false
+            }
+            WorkFunction::FetchAvailableBadges => {
+                self.link.send_future(async {
+                    match stellar::fetch_toml_currencies(&String::from(
+                        "https://quest.stellar.org/.well-known/stellar.toml",
+                    ))
+                    .await
+                    {
+                        Some(badges) => WorkFunction::FetchAvailableBadgesDone {
+                            available_badges: badges
+                                .into_iter()
+                                .filter(|b| b.code.starts_with("SQ") || b.code.starts_with("SSQ"))
+                                .collect(),
+                        },
+                        None => WorkFunction::Err(Error::Other(String::from(
+                            "The available badges could not be loaded from quest.stellar.org!",
+                        ))),
+                    }
+                });
+                false
+            }
+            WorkFunction::FetchAvailableBadgesDone { available_badges } => {
+                self.available_badges = available_badges;
+                self.link.send_message(WorkFunction::FetchBadges);
+                false
+            }
+            WorkFunction::FetchBadges => {
+                let ids = parse_ids(&self.props.ids);
+                let available = self.available_badges.clone();
+                self.link.send_future(async move {
+                    let available = &available;
+                    let mut accounts =
+                        futures::stream::iter(ids.iter().cloned().map(|id| async move {
+                            let badges =
+                                badge_check::fetch_badges(&id, available)
+                                    .await
+                                    .map_err(|err| {
+                                        err.context(format!(
+                                            "The account {} could not be checked",
+                                            id
+                                        ))
+                                    });
+                            (id, badges)
+                        }))
+                        .buffer_unordered(ResolveOptions::default().concurrency)
+                        .collect::<Vec<(String, Result<Vec<Badge>, Error>)>>()
+                        .await;
+                    // the accounts resolve out of order, the columns keep the order of the route
+                    accounts.sort_by_key(|(id, _)| ids.iter().position(|other| other == id));
+                    WorkFunction::FetchBadgesDone { accounts }
+                });
+                false
+            }
+            WorkFunction::FetchBadgesDone { accounts } => {
+                self.accounts = accounts;
+                self.link.send_message(WorkFunction::Done);
+                false
+            }
+            WorkFunction::Done | WorkFunction::Err(_) => true,
+            _ => false,
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> yew::ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        self.accounts = vec![];
+        // the available badges do not change between accounts
+        if self.available_badges.is_empty() {
+            self.link.send_message(WorkFunction::Begin);
+        } else {
+            self.link.send_message(WorkFunction::FetchBadges);
+        }
+        false
+    }
+
+    fn view(&self) -> yew::Html {
+        match self.status.clone() {
+            WorkFunction::Err(err) => html! {
+                <ErrorCard message={err.report()} code={Some(err.code())}/>
+            },
+            WorkFunction::Done => html! {
+                <div class="container">
+                    <section class="section">
+                        <h1 class="title">{"Compare accounts"}</h1>
+                        { self.view_add_account() }
+                        { self.view_failed() }
+                    </section>
+                    { self.view_matrix() }
+                    { self.view_missing() }
+                </div>
+            },
+            _ => html! {
+                <div class="container is-max-desktop">
+                    <div class="sqb-centered">
+                        <h2 class="subtitle is-centered">
+                            {format!("Loading the badges of {} accounts", parse_ids(&self.props.ids).len())}
+                        </h2>
+                    </div>
+                </div>
+            },
+        }
+    }
+}
+
+/// Splits the `ids` route parameter, dropping empty and repeated ids
+pub fn parse_ids(ids: &str) -> Vec<String> {
+    ids.split(',')
+        .map(|id| id.trim())
+        .filter(|id| !id.is_empty())
+        .unique()
+        .map(String::from)
+        .collect()
+}
+
+impl CompareView {
+    fn view_add_account(&self) -> Html {
+        let change = self
+            .link
+            .callback(|e: InputData| WorkFunction::NewAccountChange(e.value));
+        html! {
+            <div class="field has-addons">
+                <div class="control is-expanded">
+                    <input class="input" type="text" placeholder="G... account to add to the comparison"
+                           value={self.new_account.clone()} oninput={change}/>
+                </div>
+                <div class="control">
+                    <button class="button is-primary" onclick={self.link.callback(|_| WorkFunction::AddAccount)}>
+                        {"Add"}
+                    </button>
+                </div>
+            </div>
+        }
+    }
+
+    /// The accounts whose badges could be resolved
+    fn checked(&self) -> Vec<(&String, &[Badge])> {
+        self.accounts
+            .iter()
+            .filter_map(|(id, badges)| badges.as_ref().ok().map(|b| (id, b.as_slice())))
+            .collect()
+    }
+
+    fn view_failed(&self) -> Html {
+        self.accounts
+            .iter()
+            .filter_map(|(_, badges)| badges.as_ref().err())
+            .map(|err| {
+                html! {
+                    <div class="notification is-danger is-light">{err.report()}</div>
+                }
+            })
+            .collect::<Html>()
+    }
+
+    /// Quest codes of the available badges, sorted by series and quest
+    fn quest_codes(&self) -> Vec<(usize, String)> {
+        self.available_badges
+            .iter()
+            .filter_map(|c| proof_encoding::quest_position(&c.code).map(|pos| (pos, &c.code)))
+            .sorted()
+            .map(|((series, _), code)| (series, code.clone()))
+            .unique()
+            .collect()
+    }
+
+    fn view_matrix(&self) -> Html {
+        html! {
+            <section class="section">
+                <div class="table-container">
+                    <table class="table is-fullwidth is-narrow is-hoverable">
+                        <thead>
+                            <tr>
+                                <th>{"Quest"}</th>
+                                {
+                                    self.accounts.iter().map(|(id, badges)| {
+                                        let remove = id.clone();
+                                        html! {
+                                            <th title={id.clone()}>
+                                                { go_to(Route::Account { id: id.clone() }, html! { {short_account(id)} }, vec![]) }
+                                                {
+                                                    match badges {
+                                                        Ok(badges) => format!(" ({})", badge_check::owned_codes(badges).len()),
+                                                        Err(_) => String::from(" (failed)"),
+                                                    }
+                                                }
+                                                <button class="delete is-small ml-1"
+                                                        onclick={self.link.callback(move |_| WorkFunction::RemoveAccount(remove.clone()))}></button>
+                                            </th>
+                                        }
+                                    }).collect::<Html>()
+                                }
+                            </tr>
+                        </thead>
+                        <tbody>
+                        {
+                            self.quest_codes().into_iter().group_by(|(series, _)| *series).into_iter().map(|(series, codes)| html! {
+                                <>
+                                    <tr>
+                                        <th colspan={(self.accounts.len() + 1).to_string()}>{format!("Series {}", series)}</th>
+                                    </tr>
+                                    { codes.map(|(_, code)| self.view_row(&code)).collect::<Html>() }
+                                </>
+                            }).collect::<Html>()
+                        }
+                        </tbody>
+                    </table>
+                </div>
+            </section>
+        }
+    }
+
+    fn view_row(&self, code: &str) -> Html {
+        html! {
+            <tr>
+                <td>{code}</td>
+                {
+                    self.accounts.iter().map(|(id, badges)| {
+                        let badges = match badges {
+                            Ok(badges) => badges,
+                            Err(_) => return html! { <td class="has-text-danger">{"?"}</td> },
+                        };
+                        match badges.iter().find(|b| b.owned && b.token.code == code) {
+                            Some(badge) => html! {
+                                <td class="has-text-success">
+                                    { go_to(
+                                        Route::Badge { account: id.clone(), code: code.to_string() },
+                                        html! { {badge.date_accuired.clone().unwrap_or(String::from("owned"))} },
+                                        vec![],
+                                    ) }
+                                </td>
+                            },
+                            None => html! { <td class="has-text-grey-light">{"—"}</td> },
+                        }
+                    }).collect::<Html>()
+                }
+            </tr>
+        }
+    }
+
+    fn view_missing(&self) -> Html {
+        let checked = self.checked();
+        if checked.len() < 2 {
+            return Html::default();
+        }
+        html! {
+            <section class="section">
+                <h2 class="title is-4">{"Missing"}</h2>
+                {
+                    checked.iter().map(|(id, badges)| {
+                        let others = checked
+                            .iter()
+                            .filter(|(other, _)| other != id)
+                            .map(|(_, badges)| *badges)
+                            .collect::<Vec<&[Badge]>>();
+                        let missing = badge_check::missing_codes(badges, &others);
+                        html! {
+                            <p>
+                                <strong title={id.to_string()}>{short_account(id)}</strong>
+                                {
+                                    if missing.is_empty() {
+                                        String::from(" owns every badge the others own")
+                                    } else {
+                                        format!(" is missing {}", missing.join(", "))
+                                    }
+                                }
+                            </p>
+                        }
+                    }).collect::<Html>()
+                }
+            </section>
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use itertools::Itertools;
+use log::debug;
+use yew::prelude::*;
+use yew::{html, Component, ComponentLink};
+use yew_router::Routable;
+
+use crate::stellar::stellar_data::TOMLCurrency;
+use crate::stellar::*;
+use crate::util::badge_check::{self, Badge};
+use crate::util::embed::{self, EmbedOptions};
+use crate::util::error::Error;
+use crate::webpage::components::badge::{short_account, BadgeCard};
+use crate::webpage::view::Route;
+
+#[derive(Clone, Debug, Eq, PartialEq, Properties)]
+pub struct Props {
+    pub id: String,
+}
+
+/// The badges of an account without the app around them, for iframes on other sites
+pub struct EmbedView {
+    link: ComponentLink<EmbedView>,
+    props: Props,
+    options: EmbedOptions,
+    status: WorkFunction,
+    badges: Vec<Badge>,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum WorkFunction {
+    FetchAvailableBadges,
+    FetchBadges { available_badges: Vec<TOMLCurrency> },
+    FetchBadgesDone { badges: Vec<Badge> },
+    Err(Error),
+}
+
+impl Component for EmbedView {
+    type Message = WorkFunction;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            link: link,
+            props: props,
+            options: EmbedOptions::parse(&location_query()),
+            status: WorkFunction::FetchAvailableBadges,
+            badges: vec![],
+        }
+    }
+
+    fn rendered(&mut self, first_render: bool) {
+        if first_render {
+            self.link.send_message(WorkFunction::FetchAvailableBadges);
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> yew::ShouldRender {
+        debug!("WorkFunction: {:?}", msg);
+        self.status = msg.clone();
+        match msg {
+            WorkFunction::FetchAvailableBadges => {
+                self.link.send_future(async {
+                    match stellar::fetch_toml_currencies(&String::from(
+                        "https://quest.stellar.org/.well-known/stellar.toml",
+                    ))
+                    .await
+                    {
+                        Some(available_badges) => WorkFunction::FetchBadges { available_badges },
+                        None => WorkFunction::Err(Error::Other(String::from(
+                            "The available badges could not be loaded from quest.stellar.org!",
+                        ))),
+                    }
+                });
+                false
+            }
+            WorkFunction::FetchBadges { available_badges } => {
+                let id = self.props.id.clone();
+                self.link.send_future(async move {
+                    match badge_check::fetch_badges(&id, &available_badges).await {
+                        Ok(badges) => WorkFunction::FetchBadgesDone { badges },
+                        Err(err) => WorkFunction::Err(err),
+                    }
+                });
+                false
+            }
+            WorkFunction::FetchBadgesDone { badges } => {
+                self.badges = badges;
+                true
+            }
+            WorkFunction::Err(_) => true,
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> yew::ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        self.options = EmbedOptions::parse(&location_query());
+        self.link.send_message(WorkFunction::FetchAvailableBadges);
+        false
+    }
+
+    fn view(&self) -> yew::Html {
+        let classes = classes!(
+            "sqbadge-embed",
+            format!("is-{}", self.options.theme.name()),
+            format!("is-{}", self.options.size.name())
+        );
+        let content = match &self.status {
+            WorkFunction::Err(err) => html! { <p class="embed-message">{err.report()}</p> },
+            WorkFunction::FetchBadgesDone { badges: _ } => self.view_badges(),
+            _ => html! { <p class="embed-message">{"Loading badges…"}</p> },
+        };
+        html! {
+            <div class={classes}>
+                { content }
+            </div>
+        }
+    }
+}
+
+impl EmbedView {
+    fn view_badges(&self) -> Html {
+        let shown = self
+            .badges
+            .iter()
+            .filter(|b| self.options.shows(b))
+            .sorted_by(|a, b| {
+                a.token
+                    .code
+                    .cmp(&b.token.code)
+                    .then_with(|| b.owned.cmp(&a.owned))
+                    .then_with(|| a.is_mono().cmp(&b.is_mono()))
+            })
+            .unique_by(|b| &b.token.code)
+            .cloned()
+            .collect::<Vec<Badge>>();
+        let owned = shown.iter().filter(|b| b.owned).count();
+        html! {
+            <>
+                <p class="embed-header">
+                    <a href={embed::app_url(&app_base(), &Route::Account { id: self.props.id.clone() }.to_path())} target="_blank" title={self.props.id.clone()}>
+                        {short_account(&self.props.id)}
+                    </a>
+                    {format!(" · {} / {} badges", owned, shown.len())}
+                </p>
+                <div class="badges embed-badges">
+                {
+                    shown.into_iter().map(|badge| html! {
+                        <BadgeCard badge={badge} valid={true}/>
+                    }).collect::<Html>()
+                }
+                </div>
+            </>
+        }
+    }
+}
+
+/// Url the app is served from, e.g. `https://user.github.io/sqbadge` on GitHub Pages.
+/// Taken from the `<base href>` of the page when there is one, the origin otherwise.
+pub fn app_base() -> String {
+    let window = match web_sys::window() {
+        Some(window) => window,
+        None => return String::default(),
+    };
+    let base = window
+        .document()
+        .and_then(|document| match document.query_selector("base[href]") {
+            Ok(Some(_)) => document.base_uri().ok().flatten(),
+            _ => None,
+        });
+    base.or_else(|| window.location().origin().ok())
+        .unwrap_or_default()
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Query string of the current page, `?` included
+fn location_query() -> String {
+    web_sys::window()
+        .and_then(|w| w.location().search().ok())
+        .unwrap_or_default()
+}
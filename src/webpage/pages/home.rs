@@ -1,6 +1,8 @@
-use crate::js::{albedo, albedo_response};
-use crate::webpage::view::Route;
-use js_sys::JsString;
+use crate::util::error::Error;
+#[cfg(feature = "dev-signer")]
+use crate::util::local_signer::{self, LocalSigner};
+use crate::util::wallet::{self, WalletKind};
+use crate::webpage::view::{go_to, Route};
 use log::debug;
 use yew::prelude::*;
 
@@ -8,14 +10,21 @@ pub struct Home {
     link: ComponentLink<Home>,
     modal_open: bool,
     proof_text: String,
+    wallet_choice_open: bool,
+    login_error: Option<String>,
+    #[cfg(feature = "dev-signer")]
+    local_seed: String,
 }
 
 #[derive(Debug)]
 pub enum ClientEvent {
-    AlbedoRequestLogin,
-    AlbedoSuccessLogin(albedo_response::AlbedoPublicKey),
-    AlbedoFailLogin(albedo_response::AlbedoError),
-    InternalError(serde_json::Error),
+    ToggleWalletChoice,
+    WalletRequestLogin(WalletKind),
+    WalletLoginDone(WalletKind, Result<String, Error>),
+    #[cfg(feature = "dev-signer")]
+    LocalSeedChange(String),
+    #[cfg(feature = "dev-signer")]
+    UnlockLocalSigner,
     ToggleProofChoice,
     ProofUpload,
     ModalProofTextChange(String),
@@ -30,41 +39,55 @@ impl Component for Home {
             link: link,
             modal_open: false,
             proof_text: String::new(),
+            wallet_choice_open: false,
+            login_error: None,
+            #[cfg(feature = "dev-signer")]
+            local_seed: String::new(),
         }
     }
 
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
-            ClientEvent::AlbedoRequestLogin => {
-                self.link.send_future(async {
-                    let token = "stellar.badge.rs";
-                    let albedo_response =
-                        unsafe { albedo::public_key(JsString::from(token)).await };
-
-                    match albedo_response {
-                        Ok(resp) => {
-                            let res: serde_json::Result<albedo_response::AlbedoPublicKey> =
-                                resp.into_serde();
-                            match res {
-                                Ok(r) => ClientEvent::AlbedoSuccessLogin(r),
-                                Err(r) => ClientEvent::InternalError(r),
-                            }
-                        }
-                        Err(resp) => {
-                            let res: serde_json::Result<albedo_response::AlbedoError> =
-                                resp.into_serde();
-                            match res {
-                                Ok(r) => ClientEvent::AlbedoFailLogin(r),
-                                Err(r) => ClientEvent::InternalError(r),
-                            }
-                        }
-                    }
+            ClientEvent::ToggleWalletChoice => {
+                self.wallet_choice_open = !self.wallet_choice_open;
+                self.login_error = None;
+                return true;
+            }
+            ClientEvent::WalletRequestLogin(kind) => {
+                self.login_error = None;
+                self.link.send_future(async move {
+                    let pub_key = kind.provider().public_key().await;
+                    ClientEvent::WalletLoginDone(kind, pub_key)
                 });
             }
-            ClientEvent::AlbedoSuccessLogin(r) => {
-                yew_router::push_route(Route::Account { id: r.pubkey })
+            ClientEvent::WalletLoginDone(kind, Ok(pub_key)) => {
+                wallet::connect(kind);
+                yew_router::push_route(Route::Account { id: pub_key })
+            }
+            ClientEvent::WalletLoginDone(kind, Err(err)) => {
+                log::info!("{} login fail: {:?}", kind.name(), err);
+                self.login_error = Some(err.report());
+                return true;
+            }
+            #[cfg(feature = "dev-signer")]
+            ClientEvent::LocalSeedChange(seed) => {
+                self.local_seed = seed;
+            }
+            #[cfg(feature = "dev-signer")]
+            ClientEvent::UnlockLocalSigner => {
+                let seed = std::mem::take(&mut self.local_seed);
+                match LocalSigner::from_secret(&seed) {
+                    Ok(signer) => {
+                        local_signer::unlock(signer);
+                        self.link
+                            .send_message(ClientEvent::WalletRequestLogin(WalletKind::Local));
+                    }
+                    Err(err) => {
+                        self.login_error = Some(err.report());
+                        return true;
+                    }
+                }
             }
-            ClientEvent::AlbedoFailLogin(r) => log::info!("Albedo login fail: {:?}", r),
             ClientEvent::ToggleProofChoice => {
                 self.modal_open = !self.modal_open;
 
@@ -84,7 +107,6 @@ impl Component for Home {
                 self.proof_text = proof;
                 return true;
             }
-            _ => {}
         }
 
         false
@@ -104,9 +126,16 @@ impl Component for Home {
                             <div class="columns is-4 is-variable">
                                 <div class="column is-centered">
                                     <button class="button is-primary" style="width: 100%"
-                                            onclick={self.link.callback(|_| ClientEvent::AlbedoRequestLogin)}>
+                                            onclick={self.link.callback(|_| ClientEvent::ToggleWalletChoice)}>
                                             {"View your Badges!"}
                                     </button>
+                                    {
+                                        if self.wallet_choice_open {
+                                            self.render_wallet_choice()
+                                        } else {
+                                            Html::default()
+                                        }
+                                    }
                                 </div>
                                 <div class="column">
                                     <button class="button is-primary" style="width: 100%"
@@ -115,6 +144,9 @@ impl Component for Home {
                                     </button>
                                 </div>
                             </div>
+                            <p style="text-align: center">
+                                { go_to(Route::Roster, html! { {"Team leaderboard"} }, vec![]) }
+                            </p>
                     </div>
                 </div>
                 {
@@ -130,6 +162,55 @@ impl Component for Home {
 }
 
 impl Home {
+    fn render_wallet_choice(&self) -> Html {
+        html! {
+            <>
+                <div class="buttons mt-2" style="justify-content: center">
+                    {
+                        for WalletKind::BROWSER.iter().map(|&kind| html! {
+                            <button class="button is-light"
+                                    onclick={self.link.callback(move |_| ClientEvent::WalletRequestLogin(kind))}>
+                                {kind.name()}
+                            </button>
+                        })
+                    }
+                </div>
+                { self.render_local_signer() }
+                {
+                    match &self.login_error {
+                        Some(err) => html! { <p class="help is-danger" style="text-align: center">{err}</p> },
+                        None => Html::default(),
+                    }
+                }
+            </>
+        }
+    }
+
+    #[cfg(feature = "dev-signer")]
+    fn render_local_signer(&self) -> Html {
+        let seed_change = self
+            .link
+            .callback(|e: InputData| ClientEvent::LocalSeedChange(e.value));
+        html! {
+            <div class="field has-addons mt-2">
+                <div class="control is-expanded">
+                    <input class="input" type="password" placeholder="S... secret seed (dev mode, kept in memory only)"
+                           autocomplete="off" oninput={seed_change}/>
+                </div>
+                <div class="control">
+                    <button class="button is-warning" onclick={self.link.callback(|_| ClientEvent::UnlockLocalSigner)}>
+                        {WalletKind::Local.name()}
+                    </button>
+                </div>
+            </div>
+        }
+    }
+
+    #[cfg(not(feature = "dev-signer"))]
+    fn render_local_signer(&self) -> Html {
+        Html::default()
+    }
+
     fn render_modal(&self) -> Html {
         html! {
             <div class="modal is-active">
@@ -1,3 +1,7 @@
 pub mod account;
+pub mod badge;
+pub mod compare;
+pub mod embed;
 pub mod home;
 pub mod proof;
+pub mod roster;
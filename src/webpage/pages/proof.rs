@@ -2,16 +2,25 @@ use chrono::{TimeZone, Utc};
 use log::{debug, error, info, warn};
 use yew::prelude::*;
 use yew::{html, Component, ComponentLink};
+use yew_router::Routable;
 
-use crate::js::albedo;
 use crate::stellar::stellar_data::TOMLCurrency;
 use crate::stellar::*;
-use crate::util::badge_check::{self, Badge};
-use crate::util::error::{Error, ProofErr, StellarErr};
-use crate::util::proof_encoding::{self, Proof};
+use crate::util::anchor::{self, Anchor};
+use crate::util::badge_check::{self, Badge, OwnershipRequirement, ResolveOptions};
+use crate::util::certificate::Certificate;
+use crate::util::embed;
+use crate::util::error::{Error, ProofErr};
+use crate::util::proof_encoding::{self, Predicate, Proof, SignedMessage};
+use crate::util::revocation;
+use crate::util::series::{self, Series};
+use crate::util::svg;
 use crate::webpage::components::badge::BadgeCard;
 use crate::webpage::components::error::ErrorCard;
+use crate::webpage::components::series::{series_classes, view_series_title};
 use crate::webpage::html_implements;
+use crate::webpage::pages::embed::app_base;
+use crate::webpage::view::Route;
 use itertools::Itertools;
 
 #[derive(Clone, Debug, Eq, PartialEq, Properties)]
@@ -25,7 +34,13 @@ pub struct ProofStorage {
     pub proof_claim: Option<Proof>,
     pub owned_badges: Option<Vec<Badge>>,
     pub account: Option<String>,
+    /// The account and all cosigners of the proof
+    pub accounts: Vec<String>,
     pub valid: bool,
+    /// None if the proof is not anchored on-chain
+    pub anchor: Option<Result<Anchor, Error>>,
+    /// The signer which revoked the proof
+    pub revoked_by: Option<String>,
 }
 
 pub struct ProofVerify {
@@ -33,19 +48,30 @@ pub struct ProofVerify {
     props: Props,
     status: LoadStatus,
     proof: ProofStorage,
-    decoded_proof: Option<(bool, String, String)>,
+    decoded_proof: Option<SignedMessage>,
+    requirement: OwnershipRequirement,
+    show_revoked: bool,
+    show_certificate: bool,
 }
 
 #[derive(PartialEq, Clone, Debug)]
 pub enum LoadStatus {
     Begin,
+    CheckRevocation,
+    CheckRevocationDone(Result<Option<String>, Error>),
     FetchAvailableBadges,
     FetchAvailableBadgesDone { available_badges: Vec<TOMLCurrency> },
     CheckProof,
     FetchOwnedBadges,
     FetchOwnedBadgesDone { owned_badges: Vec<Badge> },
+    CheckAnchor,
+    CheckAnchorDone(Result<Option<Anchor>, Error>),
+    SetRequirement(OwnershipRequirement),
+    ShowRevoked,
+    ToggleCertificate,
+    PrintCertificate,
     Done,
-    Err(String),
+    Err(Error),
     None,
 }
 
@@ -60,6 +86,9 @@ impl Component for ProofVerify {
             status: LoadStatus::None,
             proof: ProofStorage::default(),
             decoded_proof: None,
+            requirement: OwnershipRequirement::default(),
+            show_revoked: false,
+            show_certificate: false,
         }
     }
 
@@ -78,30 +107,46 @@ impl Component for ProofVerify {
             LoadStatus::Begin => {
                 if let Some(err) = self.decrypt_proof() {
                     // proof decryption failed:
-                    let message = match err.clone() {
-                        Error::StellarErr(StellarErr::InvalidPublicKey) => {
-                            format!("The proof contains a public key of an invalid format!")
-                        }
-                        Error::ProofErr(_) => {
-                            format!("The given proof could not be decoded!")
-                        }
-                        _ => {
-                            format!("An unknown error occured. {:?}", err)
-                        }
-                    };
-                    self.link.send_message(LoadStatus::Err(message))
+                    self.link.send_message(LoadStatus::Err(
+                        err.context("The given proof could not be read"),
+                    ))
                 } else {
-                    self.link.send_message(LoadStatus::FetchAvailableBadges);
+                    self.link.send_message(LoadStatus::CheckRevocation);
                 }
                 true
             }
+            LoadStatus::CheckRevocation => {
+                let signed = self.decoded_proof.clone().unwrap_or_default();
+                self.link.send_future(async move {
+                    LoadStatus::CheckRevocationDone(revocation::check(&signed).await)
+                });
+                false
+            }
+            LoadStatus::CheckRevocationDone(revoked_by) => {
+                match revoked_by {
+                    Ok(revoked_by) => {
+                        self.proof.revoked_by = revoked_by;
+                        self.link.send_message(LoadStatus::FetchAvailableBadges);
+                    }
+                    Err(err) => self.link.send_message(LoadStatus::Err(
+                        err.context("The revocations of the proof could not be checked"),
+                    )),
+                }
+                false
+            }
             LoadStatus::FetchAvailableBadges => {
                 self.link.send_future(async {
                     let badges = stellar::fetch_toml_currencies(&String::from(
                         "https://quest.stellar.org/.well-known/stellar.toml",
                     ))
-                    .await
-                    .unwrap();
+                    .await;
+                    let badges =
+                        match badges {
+                            Some(badges) => badges,
+                            None => return LoadStatus::Err(Error::Other(String::from(
+                                "The available badges could not be loaded from quest.stellar.org!",
+                            ))),
+                        };
                     let badges = badges
                         .into_iter()
                         .filter(|b| b.code.starts_with("SQ") || b.code.starts_with("SSQ"))
@@ -122,55 +167,53 @@ impl Component for ProofVerify {
                 let err_decrypting = !self.decrypt_badges();
 
                 if err_decrypting {
-                    self.link
-                        .send_message(LoadStatus::Err(String::from("Invalid proof!")));
+                    self.link.send_message(LoadStatus::Err(Error::ProofErr(
+                        ProofErr::ProofInvalidEncoding,
+                    )));
                 } else {
                     self.link.send_message(LoadStatus::FetchOwnedBadges);
                 }
                 false
             }
             LoadStatus::FetchOwnedBadges => {
-                let pub_key = self.proof.account.clone().unwrap().clone();
-                let available_badges = self.proof.available_badges.clone();
-
-                if available_badges.is_none() {
-                    warn!("Invalid load state: available badges are None!");
-                    self.link.send_message(LoadStatus::Err(String::from(
-                        "Invalid data received for: available_badges in None!",
-                    )));
+                let accounts = self.proof.accounts.clone();
+                if accounts.is_empty() {
+                    self.link
+                        .send_message(LoadStatus::Err(Error::Other(String::from(
+                            "The proof does not contain an account!",
+                        ))));
                     return false;
                 }
+                let available_badges = self.proof.available_badges.clone();
 
-                let available_badges = available_badges.unwrap();
-
-                self.link.send_future(async move {
-                    let in_possession =
-                        badge_check::fetch_badges(&pub_key, &available_badges).await;
-
-                    if in_possession.is_err() {
-                        // Sth went wrong fetching --> probably wrong account id (if not handled inbefore ._.)
-                        let err =  in_possession.err().unwrap();
-
-                        if let Error::StellarErr(s_err) = err  {
-                            return LoadStatus::Err(match s_err {
-                                StellarErr::AccountNotFound => {
-                                    format!("The account ({}) specified in the proof could not be found!", pub_key)
-                                },
-                                StellarErr::InvalidPublicKey => {
-                                    format!("The public key embedded in the proof is not in a valid ed25519 format!")
-                                },
-                                _ => {
-                                    format!("Unknown error while trying to connect to the stellar network!")
-                                }
-
-                            });
-                        }
-
-                        return LoadStatus::Err(format!("{:?}", err));
+                let available_badges = match available_badges {
+                    Some(available_badges) => available_badges,
+                    None => {
+                        warn!("Invalid load state: available badges are None!");
+                        self.link
+                            .send_message(LoadStatus::Err(Error::Other(String::from(
+                                "Invalid data received for: available_badges in None!",
+                            ))));
+                        return false;
                     }
+                };
 
-                    LoadStatus::FetchOwnedBadgesDone {
-                        owned_badges: in_possession.unwrap(),
+                let options = ResolveOptions {
+                    assets: self.requested_assets(&available_badges),
+                    ..ResolveOptions::default()
+                };
+                self.link.send_future(async move {
+                    match badge_check::fetch_badges_of_accounts(
+                        &accounts,
+                        &available_badges,
+                        &options,
+                    )
+                    .await
+                    {
+                        Ok(owned_badges) => LoadStatus::FetchOwnedBadgesDone { owned_badges },
+                        Err(err) => LoadStatus::Err(
+                            err.context("The badges of the proof could not be checked"),
+                        ),
                     }
                 });
                 false
@@ -178,9 +221,45 @@ impl Component for ProofVerify {
             LoadStatus::FetchOwnedBadgesDone { owned_badges } => {
                 self.proof.owned_badges = Some(owned_badges.clone());
                 debug!("Loaded owned badges: {:?}", owned_badges);
+                self.link.send_message(LoadStatus::CheckAnchor);
+                false
+            }
+            LoadStatus::CheckAnchor => {
+                let signed = self.decoded_proof.clone().unwrap_or_default();
+                self.link.send_future(async move {
+                    LoadStatus::CheckAnchorDone(anchor::verify(&signed).await)
+                });
+                false
+            }
+            LoadStatus::CheckAnchorDone(anchor) => {
+                debug!("Checked anchor: {:?}", anchor);
+                // a failed lookup only weakens the proof, the badges are still shown
+                self.proof.anchor = anchor.transpose();
                 self.link.send_message(LoadStatus::Done);
                 false
             }
+            LoadStatus::SetRequirement(requirement) => {
+                self.requirement = requirement;
+                self.status = LoadStatus::Done;
+                true
+            }
+            LoadStatus::ShowRevoked => {
+                self.show_revoked = true;
+                self.status = LoadStatus::Done;
+                true
+            }
+            LoadStatus::ToggleCertificate => {
+                self.show_certificate = !self.show_certificate;
+                self.status = LoadStatus::Done;
+                true
+            }
+            LoadStatus::PrintCertificate => {
+                if let Some(Err(err)) = web_sys::window().map(|w| w.print()) {
+                    warn!("Printing the certificate: {:?}", err);
+                }
+                self.status = LoadStatus::Done;
+                false
+            }
             LoadStatus::Done => {
                 debug!("Finished Loading!");
                 debug!("{:?}", self.proof);
@@ -196,34 +275,144 @@ impl Component for ProofVerify {
 
     fn view(&self) -> yew::Html {
         match self.status.clone() {
-            LoadStatus::Err(msg) => self.view_err(&msg),
-            LoadStatus::Done => self.view_account(),
+            LoadStatus::Err(err) => self.view_err(&err),
+            LoadStatus::Done => match (&self.proof.revoked_by, self.show_revoked) {
+                (Some(revoked_by), false) => self.view_revoked(revoked_by),
+                _ if self.show_certificate && self.verified() => self.view_certificate(),
+                _ => self.view_account(),
+            },
             other => self.view_loading(other),
         }
     }
 }
 
 impl ProofVerify {
-    fn render_series(&self, series: &String, badges: &Vec<Badge>) -> Html {
-        let claimed_owned_badges = self
+    /// Assets the proof makes claims about: the claimed badges and the series of its predicates
+    fn requested_assets(&self, available_badges: &[TOMLCurrency]) -> Vec<String> {
+        let claimed = self.claimed_codes();
+        let series = self
             .proof
+            .proof_claim
+            .as_ref()
+            .map(|p| p.predicates.clone())
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|p| match p {
+                Predicate::CompleteSeries(series) => Some(series),
+                Predicate::AtLeast(_) => None,
+            })
+            .collect::<Vec<usize>>();
+        available_badges
+            .iter()
+            .filter(|b| {
+                claimed.contains(&b.code)
+                    || proof_encoding::quest_position(&b.code)
+                        .map(|(s, _)| series.contains(&s))
+                        .unwrap_or(false)
+            })
+            .map(|b| b.asset())
+            .unique()
+            .collect()
+    }
+
+    fn claimed_codes(&self) -> Vec<String> {
+        self.proof
             .proof_claim
             .clone()
-            .unwrap()
+            .unwrap_or(Proof::default())
             .owned_badges
             .into_iter()
             .map(|t| t.code)
-            .collect::<Vec<String>>();
+            .collect()
+    }
+
+    /// Badges claimed by the proof that meet the requirement
+    fn proven_badges(&self) -> Vec<Badge> {
+        let claimed = self.claimed_codes();
+        self.proof
+            .owned_badges
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|b| claimed.contains(&b.token.code) && self.requirement.is_met_by(b))
+            .collect()
+    }
+
+    /// Whether the signature is valid, the proof not revoked and all its claims met
+    fn verified(&self) -> bool {
+        let owned_badges = self.proof.owned_badges.clone().unwrap_or_default();
+        let proven = self.proven_badges();
+        let proof_claim = self.proof.proof_claim.clone().unwrap_or_default();
+        self.proof.valid
+            && self.proof.revoked_by.is_none()
+            && proof_claim
+                .owned_badges
+                .iter()
+                .all(|t| proven.iter().any(|b| b.token.code == t.code))
+            && proof_claim
+                .predicates
+                .iter()
+                .all(|p| badge_check::predicate_met(p, &owned_badges, &self.requirement))
+    }
+
+    fn certificate(&self) -> Certificate {
+        let claimed = self.claimed_codes();
+        let series = series::progress(
+            &self.proof.available_badges.clone().unwrap_or_default(),
+            &self.proof.owned_badges.clone().unwrap_or_default(),
+            |b| claimed.contains(&b.token.code) && self.requirement.is_met_by(b),
+        );
+        let anchor = match &self.proof.anchor {
+            Some(Ok(Anchor::Confirmed { tx_hash, .. })) => Some(tx_hash.clone()),
+            _ => None,
+        };
+        Certificate {
+            accounts: self.proof.accounts.clone(),
+            badges: self.proven_badges(),
+            series: series.into_iter().filter(|s| s.is_complete()).collect(),
+            verify_url: embed::app_url(
+                &app_base(),
+                &Route::Proof {
+                    id: self.props.proof.clone(),
+                }
+                .to_path(),
+            ),
+            anchor,
+            issued_at: Utc::now(),
+        }
+    }
 
+    fn view_certificate(&self) -> Html {
+        let certificate = self.certificate().render();
+        let account = self.proof.account.clone().unwrap_or_default();
+        let url = svg::data_url("image/svg+xml", certificate.as_bytes());
+        html! {
+            <div class="certificate-page">
+                <div class="buttons certificate-actions" style="justify-content: center">
+                    <button class="button" onclick={self.link.callback(|_| LoadStatus::ToggleCertificate)}>{"Back to the proof"}</button>
+                    <button class="button is-primary" onclick={self.link.callback(|_| LoadStatus::PrintCertificate)}>{"Print"}</button>
+                    <a class="button" href={url.clone()} download={format!("certificate-{}.svg", account)}>{"Download SVG"}</a>
+                </div>
+                <img class="certificate" src={url} alt="Certificate of completion"/>
+            </div>
+        }
+    }
+
+    fn render_series(&self, group: &String, series: Option<&Series>, badges: &Vec<Badge>) -> Html {
+        let claimed_owned_badges = self.claimed_codes();
+
+        // the badge of the account meeting the requirement, if any
         let colored_badges = badges
             .clone()
             .into_iter()
-            .filter(|b| b.owned)
+            .filter(|b| self.requirement.is_met_by(b))
+            .chain(badges.clone().into_iter().filter(|b| b.owned))
             .chain(badges.clone().into_iter().filter(|b| !b.is_mono()))
             .unique_by(|b| b.token.code.clone())
             .sorted_by(|a, b| a.token.code.cmp(&b.token.code))
             .map(|b| -> Html {
-                let valid = !(claimed_owned_badges.contains(&b.token.code) && !b.owned);
+                let valid = !(claimed_owned_badges.contains(&b.token.code)
+                    && !self.requirement.is_met_by(&b));
 
                 if !valid {
                     error!(
@@ -233,14 +422,14 @@ impl ProofVerify {
                 }
 
                 html! {
-                    <BadgeCard badge={b.clone()} valid={valid}/>
+                    <BadgeCard badge={b.clone()} valid={valid} show_account={self.proof.accounts.len() > 1}/>
                 }
             })
             .collect::<Html>();
 
         html! {
-            <section class="section">
-            <h1 class="title" style="text-align: center">{series}</h1>
+            <section class={series_classes(series)}>
+            { view_series_title(group, series) }
             {
                 colored_badges
             }
@@ -255,6 +444,7 @@ impl ProofVerify {
             .unwrap_or(vec![])
             .into_iter()
             .filter(|b| b.owned)
+            .unique_by(|b| b.token.asset())
             .count();
         let completed_num = self
             .proof
@@ -262,7 +452,7 @@ impl ProofVerify {
             .clone()
             .unwrap_or(vec![])
             .into_iter()
-            .filter(|b| b.owned)
+            .filter(|b| self.requirement.is_met_by(b))
             .unique_by(|b| b.token.code.clone())
             .count();
         let badges_num = self
@@ -284,81 +474,246 @@ impl ProofVerify {
             .unique_by(|b| b.code.clone())
             .count();
 
-        let proof_claim = self.proof.proof_claim.clone().unwrap();
+        let proof_claim = self.proof.proof_claim.clone().unwrap_or(Proof::default());
+        let account = self.proof.account.clone().unwrap_or_default();
+
+        let claimed_codes = proof_claim
+            .owned_badges
+            .iter()
+            .map(|t| t.code.clone())
+            .collect::<Vec<String>>();
+        let owned_badges = self.proof.owned_badges.clone().unwrap_or(vec![]);
+        let unmet_claims = claimed_codes
+            .iter()
+            .unique()
+            .filter(|code| {
+                !owned_badges
+                    .iter()
+                    .any(|b| &b.token.code == *code && self.requirement.is_met_by(b))
+            })
+            .count();
+        let predicates = proof_claim
+            .predicates
+            .iter()
+            .map(|p| {
+                (
+                    *p,
+                    badge_check::predicate_met(p, &owned_badges, &self.requirement),
+                )
+            })
+            .collect::<Vec<(Predicate, bool)>>();
+        let claims_met = unmet_claims == 0 && predicates.iter().all(|(_, met)| *met);
+
+        let not_earned_num = self
+            .proof
+            .owned_badges
+            .clone()
+            .unwrap_or(vec![])
+            .into_iter()
+            .filter(|b| b.owned && !b.is_earned() && claimed_codes.contains(&b.token.code))
+            .unique_by(|b| b.token.code.clone())
+            .count();
 
         let mut proof_message = String::from("This proof was signed");
-        let has_message = proof_claim.timestamp.is_some() || proof_claim.unique_id.is_some();
+        if let Some(signed) = &self.decoded_proof {
+            proof_message.push_str(&format!(" ({} signature)", signed.scheme.describe()));
+        }
 
-        if proof_claim.timestamp.is_some() {
-            proof_message.push_str(&format!(
-                " on `{}`",
-                Utc.timestamp(proof_claim.timestamp.unwrap(), 0)
-            ));
+        if let Some(date) = proof_claim
+            .timestamp
+            .and_then(|t| Utc.timestamp_opt(t, 0).single())
+        {
+            proof_message.push_str(&format!(" on `{}`", date));
+        }
+        if let Some(unique_id) = proof_claim.unique_id {
+            proof_message.push_str(&format!(" with message `{}`", unique_id));
         }
-        if proof_claim.unique_id.is_some() {
-            proof_message.push_str(&format!(
-                " with message `{}`",
-                proof_claim.unique_id.unwrap()
-            ));
+        if let Some(Ok(Anchor::Confirmed { .. })) = &self.proof.anchor {
+            proof_message.push_str(" (self-asserted, see the on-chain anchor below)");
         }
 
         html! {
             <>
                 <h2 class="title mid-center" style="text-align: center">
                     {"Account "}
-                    <a href={format!("https://stellar.expert/explorer/public/account/{}", &self.proof.account.clone().unwrap())}>
-                        {&self.proof.account.clone().unwrap()}
+                    <a href={format!("https://stellar.expert/explorer/public/account/{}", &account)}>
+                        {&account}
                     </a>
                 </h2>
+                { self.view_cosigners() }
                 <p style="text-align: center" class="mid-center">
-                    {format!(" Earned {}/{} Badges", completed_num, badges_num)}
+                    {format!(" {}/{} Badges {}", completed_num, badges_num, self.requirement.describe())}
                     {
                         if owned_num > badges_num {
                             format!(
                              " (Owns {} / {} including mono badges)",
                              owned_num,
-                             self.proof.owned_badges.clone().unwrap_or(vec![]).len())
+                             self.proof.owned_badges.clone().unwrap_or(vec![]).iter().unique_by(|b| b.token.asset()).count())
                         } else {
                             "".to_string()
                         }
                     }
                 </p>
+                { self.view_requirement_picker() }
+                <p style="text-align: center; color:red" class="mid-center" hidden={self.proof.revoked_by.is_none()}>
+                    {"Revoked Proof! The proof was revoked by one of its signers!"}
+                </p>
                 <p style="text-align: center; color:red" class="mid-center" hidden={self.proof.valid}>
                     {format!("Invalid Proof! The given signature is invalid!")}
                 </p>
-                <p style="text-align: center; color:red" class="mid-center" hidden={claimed_num == completed_num}>
-                    {format!("Invalid Proof! Claimed to have completed {} quests!", claimed_num)}
+                <p style="text-align: center; color:red" class="mid-center" hidden={unmet_claims == 0}>
+                    {format!("Invalid Proof! Claimed to have completed {} quests, {} of them are not {}!", claimed_num, unmet_claims, self.requirement.describe())}
+                </p>
+                <p style="text-align: center" class="mid-center" hidden={claimed_num == 0 || claimed_num >= completed_num}>
+                    {format!("The proof discloses {} of the badges", claimed_num)}
                 </p>
+                { view_predicates(&predicates) }
 
-                <p style="text-align: center; color:green" class="mid-center" hidden={(claimed_num != completed_num || !self.proof.valid) && has_message}>
+                <p style="text-align: center; color:orange" class="mid-center" hidden={not_earned_num == 0}>
+                    {format!("{} of the claimed badges were not earned from the issuer (transferred in or of unknown origin)!", not_earned_num)}
+                </p>
+
+                <p style="text-align: center; color:green" class="mid-center" hidden={!claims_met || !self.proof.valid || self.proof.revoked_by.is_some()}>
                     {
                         proof_message
                     }
                 </p>
+                { self.view_anchor() }
+                {
+                    if self.verified() {
+                        html! {
+                            <div class="buttons mid-center" style="justify-content: center">
+                                <button class="button is-small" onclick={self.link.callback(|_| LoadStatus::ToggleCertificate)}>
+                                    {"Printable certificate"}
+                                </button>
+                            </div>
+                        }
+                    } else {
+                        Html::default()
+                    }
+                }
 
+                { self.view_series() }
+            </>
+        }
+    }
+    /// Badges grouped by series, a quest counts towards a series if it is claimed and meets the requirement
+    fn view_series(&self) -> Html {
+        let badges = self.proof.owned_badges.clone().unwrap_or_default();
+        let claimed = self.claimed_codes();
+        let progress = series::progress(
+            &self.proof.available_badges.clone().unwrap_or_default(),
+            &badges,
+            |b| claimed.contains(&b.token.code) && self.requirement.is_met_by(b),
+        );
+        html! {
+            <div class="badges">
+            {
+                badges.into_iter()
+                    .sorted_by_key(|badge| series::group_key(&badge.token.code))
+                    .group_by(|badge| series::group_key(&badge.token.code))
+                    .into_iter()
+                    .map(|(group, badges)| {
+                        let series = progress.iter().find(|s| s.group() == group);
+                        self.render_series(&group, series, &badges.collect())
+                    })
+                    .collect::<Html>()
+            }
+            </div>
+        }
+    }
 
-                <div class="badges">
+    fn view_revoked(&self, revoked_by: &str) -> Html {
+        html! {
+            <article class="message mid-center is-danger" style="margin-top: 1.5rem">
+                <div class="message-header">
+                    <p>{"Revoked"}</p>
+                </div>
+                <div class="message-body">
+                    <p>{format!("This proof was revoked by {}, it must not be trusted anymore.", revoked_by)}</p>
+                    <button class="button is-small mt-1" onclick={self.link.callback(|_| LoadStatus::ShowRevoked)}>
+                        {"Show the badges anyway"}
+                    </button>
+                </div>
+            </article>
+        }
+    }
+    fn view_cosigners(&self) -> Html {
+        if self.proof.accounts.len() < 2 {
+            return Html::default();
+        }
+        html! {
+            <p style="text-align: center" class="mid-center">
+                {"together with "}
                 {
-                    self.proof.owned_badges.clone()
-                        .unwrap_or(vec![]).into_iter()
-                        .group_by(|badge| {
-                            let mut series = badge.token.code.clone();
-                            if series.starts_with("SSQ") {
-                                series.truncate(3);
-                            } else {
-                                series.truncate(4);
-                            }
-                            series
-                        }).into_iter()
-                        .map(|(series, badges)|self.render_series(&series, &badges.collect()))
+                    self.proof.accounts.iter().skip(1)
+                        .map(|account| html! {
+                            <a style="margin-right: 0.5em" href={format!("https://stellar.expert/explorer/public/account/{}", account)}>
+                                {account}
+                            </a>
+                        })
                         .collect::<Html>()
                 }
-                </div>
-            </>
+            </p>
+        }
+    }
+    fn view_anchor(&self) -> Html {
+        let tx_link = |tx_hash: &String| {
+            html! {
+                <a href={format!("https://stellar.expert/explorer/public/tx/{}", tx_hash)}>{"transaction"}</a>
+            }
+        };
+        match &self.proof.anchor {
+            Some(Ok(Anchor::Confirmed {
+                tx_hash,
+                ledger,
+                closed_at,
+            })) => html! {
+                <p style="text-align: center; color:green" class="mid-center">
+                    {format!("Anchored on-chain: the proof existed at `{}` (ledger {}) at the latest, see the ", closed_at, ledger)}
+                    { tx_link(tx_hash) }
+                </p>
+            },
+            Some(Ok(Anchor::Mismatch { tx_hash, reason })) => html! {
+                <p style="text-align: center; color:red" class="mid-center">
+                    {format!("Invalid anchor: {}! See the ", reason)}
+                    { tx_link(tx_hash) }
+                </p>
+            },
+            Some(Err(err)) => html! {
+                <p style="text-align: center; color:orange" class="mid-center">
+                    {format!("The on-chain anchor could not be checked: {}", err.report())}
+                </p>
+            },
+            None => Html::default(),
+        }
+    }
+    fn view_requirement_picker(&self) -> Html {
+        let button = |requirement: OwnershipRequirement| -> Html {
+            let class = if self.requirement == requirement {
+                "button is-small is-primary is-selected"
+            } else {
+                "button is-small"
+            };
+            html! {
+                <button class={class} onclick={self.link.callback(move |_| LoadStatus::SetRequirement(requirement))}>
+                    {requirement.describe()}
+                </button>
+            }
+        };
+        html! {
+            <div class="buttons has-addons mid-center" style="justify-content: center">
+                { button(OwnershipRequirement::Earned) }
+                { button(OwnershipRequirement::Held) }
+                { button(OwnershipRequirement::EarnedAndHeld) }
+            </div>
         }
     }
     fn view_loading(&self, status: LoadStatus) -> Html {
         let description = match status {
+            LoadStatus::CheckRevocation | LoadStatus::CheckRevocationDone(_) => {
+                String::from("Checking whether the proof was revoked...")
+            }
             LoadStatus::Begin
             | LoadStatus::FetchAvailableBadges
             | LoadStatus::FetchAvailableBadgesDone {
@@ -367,6 +722,9 @@ impl ProofVerify {
             LoadStatus::FetchOwnedBadges | LoadStatus::FetchOwnedBadgesDone { owned_badges: _ } => {
                 String::from("Verifying users badges...")
             }
+            LoadStatus::CheckAnchor | LoadStatus::CheckAnchorDone(_) => {
+                String::from("Looking up the on-chain anchor...")
+            }
             _ => String::from("unknown"),
         };
         debug! {"{:?}", status};
@@ -379,52 +737,67 @@ impl ProofVerify {
             </div>
         }
     }
-    fn view_err(&self, message: &String) -> Html {
+    fn view_err(&self, err: &Error) -> Html {
         html! {
-            <ErrorCard message={message.clone()}/>
+            <ErrorCard message={err.report()} code={Some(err.code())}/>
         }
     }
 
     fn decrypt_proof(&mut self) -> Option<Error> {
-        let proof = proof_encoding::verify_albedo_signed_message(&self.props.proof);
+        let (valid, signed) = match proof_encoding::verify_signed_message(&self.props.proof) {
+            Ok(proof) => proof,
+            Err(err) => return Some(err),
+        };
 
-        if proof.is_err() {
-            return Some(proof.err().unwrap());
+        if signed.network != proof_encoding::PUBLIC_NETWORK {
+            return Some(Error::Other(format!(
+                "The proof was signed for the `{}` network, badges only exist on the public network!",
+                signed.network
+            )));
         }
 
-        let proof = proof.unwrap();
-
-        self.decoded_proof = Some(proof.clone());
-
-        self.proof.valid = proof.0;
-        self.proof.account = Some(proof.2);
+        self.proof.valid = valid;
+        self.proof.account = Some(signed.pubkey.clone());
+        self.proof.accounts = signed.accounts();
+        self.decoded_proof = Some(signed);
 
         None
     }
 
     fn decrypt_badges(&mut self) -> bool {
-        let proof = self.decoded_proof.clone();
-        if self.proof.available_badges.is_none() {
-            return false;
-        }
-        if proof.is_none() {
-            return false;
-        }
-
-        let proof = proof.unwrap();
-
-        let decrypted_badges = proof_encoding::Proof::decode_v1(
-            &proof.1,
-            &self.proof.available_badges.clone().unwrap(),
-        );
+        let (proof, available_badges) = match (
+            self.decoded_proof.clone(),
+            self.proof.available_badges.clone(),
+        ) {
+            (Some(proof), Some(available_badges)) => (proof, available_badges),
+            _ => return false,
+        };
 
-        if decrypted_badges.is_err() {
-            return false;
-        }
-        let decrypted_badges = decrypted_badges.unwrap();
+        let decrypted_badges =
+            match proof_encoding::Proof::decode(&proof.message, &available_badges) {
+                Ok(decrypted_badges) => decrypted_badges,
+                Err(_) => return false,
+            };
 
         debug!("Proof claims ownership over: {:?}", decrypted_badges);
         self.proof.proof_claim = Some(decrypted_badges);
         return true;
     }
 }
+
+fn view_predicates(predicates: &[(Predicate, bool)]) -> Html {
+    predicates
+        .iter()
+        .map(|(predicate, met)| {
+            let (color, verdict) = match met {
+                true => ("green", "verified"),
+                false => ("red", "not met"),
+            };
+            html! {
+                <p style={format!("text-align: center; color:{}", color)} class="mid-center">
+                    {format!("The proof claims the account {}: {}", predicate.describe(), verdict)}
+                </p>
+            }
+        })
+        .collect::<Html>()
+}
@@ -0,0 +1,376 @@
+use chrono::Utc;
+use futures::stream::StreamExt;
+use log::debug;
+use wasm_bindgen_futures::JsFuture;
+use yew::prelude::*;
+use yew::{html, Component, ComponentLink};
+
+use crate::js::fetch;
+use crate::stellar::stellar_data::TOMLCurrency;
+use crate::stellar::*;
+use crate::util::badge_check::{self, Badge};
+use crate::util::error::Error;
+use crate::util::roster::{self, CachedRoster, Leaderboard, Member};
+use crate::webpage::components::error::ErrorCard;
+use crate::webpage::view::{go_to, Route};
+
+/// Amount of members whose badges are resolved at the same time,
+/// each of them scanning several issuers
+const CONCURRENT_MEMBERS: usize = 2;
+
+pub struct RosterView {
+    link: ComponentLink<RosterView>,
+    status: WorkFunction,
+    roster: CachedRoster,
+    import_text: String,
+    import_url: String,
+    import_error: Option<Error>,
+}
+
+#[derive(Clone, Debug)]
+pub enum WorkFunction {
+    ImportTextChange(String),
+    ImportUrlChange(String),
+    ImportFile(Option<web_sys::File>),
+    ImportText,
+    ImportUrl,
+    Imported(Result<String, Error>),
+    Clear,
+    Refresh,
+    FetchAvailableBadgesDone {
+        available_badges: Vec<TOMLCurrency>,
+    },
+    FetchBadgesDone {
+        badges: Vec<Result<Vec<Badge>, Error>>,
+    },
+    Done,
+    Err(Error),
+}
+
+impl Component for RosterView {
+    type Message = WorkFunction;
+    type Properties = ();
+
+    fn create(_props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            link: link,
+            status: WorkFunction::Done,
+            roster: roster::load().unwrap_or_default(),
+            import_text: String::new(),
+            import_url: String::new(),
+            import_error: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> yew::ShouldRender {
+        debug!("WorkFunction: {:?}", msg);
+        match msg {
+            WorkFunction::ImportTextChange(text) => {
+                self.import_text = text;
+                false
+            }
+            WorkFunction::ImportUrlChange(url) => {
+                self.import_url = url;
+                false
+            }
+            WorkFunction::ImportText => {
+                self.link
+                    .send_message(WorkFunction::Imported(Ok(self.import_text.clone())));
+                false
+            }
+            WorkFunction::ImportUrl => {
+                let url = self.import_url.trim().to_string();
+                self.link.send_future(async move {
+                    let text = fetch::get_text(&url).await.map_err(|_| {
+                        Error::Other(format!("The roster could not be loaded from {}!", url))
+                    });
+                    WorkFunction::Imported(text)
+                });
+                false
+            }
+            WorkFunction::ImportFile(file) => {
+                if let Some(file) = file {
+                    self.link.send_future(async move {
+                        let text = JsFuture::from(file.text())
+                            .await
+                            .ok()
+                            .and_then(|text| text.as_string())
+                            .ok_or(Error::Other(String::from(
+                                "The roster file could not be read!",
+                            )));
+                        WorkFunction::Imported(text)
+                    });
+                }
+                false
+            }
+            WorkFunction::Imported(text) => {
+                match text.and_then(|text| roster::parse(&text)) {
+                    Ok(members) => {
+                        self.import_error = None;
+                        self.import_text = String::new();
+                        self.roster = CachedRoster {
+                            members,
+                            leaderboard: None,
+                        };
+                        roster::store(&self.roster);
+                        self.link.send_message(WorkFunction::Refresh);
+                    }
+                    Err(err) => self.import_error = Some(err),
+                }
+                true
+            }
+            WorkFunction::Clear => {
+                roster::clear();
+                self.roster = CachedRoster::default();
+                self.status = WorkFunction::Done;
+                true
+            }
+            WorkFunction::Refresh => {
+                self.status = WorkFunction::Refresh;
+                self.link.send_future(async {
+                    match stellar::fetch_toml_currencies(&String::from(
+                        "https://quest.stellar.org/.well-known/stellar.toml",
+                    ))
+                    .await
+                    {
+                        Some(badges) => WorkFunction::FetchAvailableBadgesDone {
+                            available_badges: badges
+                                .into_iter()
+                                .filter(|b| b.code.starts_with("SQ") || b.code.starts_with("SSQ"))
+                                .collect(),
+                        },
+                        None => WorkFunction::Err(Error::Other(String::from(
+                            "The available badges could not be loaded from quest.stellar.org!",
+                        ))),
+                    }
+                });
+                true
+            }
+            WorkFunction::FetchAvailableBadgesDone { available_badges } => {
+                let accounts = self
+                    .roster
+                    .members
+                    .iter()
+                    .map(|m| m.account.clone())
+                    .collect::<Vec<String>>();
+                self.link.send_future(async move {
+                    let badges = fetch_roster_badges(accounts, &available_badges).await;
+                    WorkFunction::FetchBadgesDone { badges }
+                });
+                false
+            }
+            WorkFunction::FetchBadgesDone { badges } => {
+                // a member that could not be checked is listed apart instead of ranked last
+                let mut members = vec![];
+                let mut resolved = vec![];
+                let mut failed = vec![];
+                for (member, badges) in self.roster.members.iter().zip(badges) {
+                    match badges {
+                        Ok(badges) => {
+                            members.push(member.clone());
+                            resolved.push(badges);
+                        }
+                        Err(err) => failed.push((member.clone(), err.report())),
+                    }
+                }
+                let mut leaderboard =
+                    roster::leaderboard(&members, &resolved, Utc::now().to_rfc3339());
+                leaderboard.failed = failed;
+                self.roster.leaderboard = Some(leaderboard);
+                roster::store(&self.roster);
+                self.link.send_message(WorkFunction::Done);
+                false
+            }
+            WorkFunction::Done | WorkFunction::Err(_) => {
+                self.status = msg;
+                true
+            }
+        }
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> yew::ShouldRender {
+        false
+    }
+
+    fn view(&self) -> yew::Html {
+        html! {
+            <div class="container is-max-desktop">
+                <section class="section">
+                    <h1 class="title">{"Leaderboard"}</h1>
+                    {
+                        match &self.status {
+                            WorkFunction::Err(err) => html! {
+                                <ErrorCard message={err.report()} code={Some(err.code())}/>
+                            },
+                            WorkFunction::Done => Html::default(),
+                            _ => html! {
+                                <p class="subtitle">
+                                    {format!("Resolving the badges of {} accounts...", self.roster.members.len())}
+                                </p>
+                            },
+                        }
+                    }
+                    {
+                        match &self.roster.leaderboard {
+                            Some(leaderboard) => self.view_leaderboard(leaderboard),
+                            None if self.roster.members.is_empty() => Html::default(),
+                            None => self.view_members(),
+                        }
+                    }
+                </section>
+                { self.view_import() }
+            </div>
+        }
+    }
+}
+
+/// Resolves the badges of each of the `accounts` on its own, in their order
+async fn fetch_roster_badges(
+    accounts: Vec<String>,
+    available_badges: &[TOMLCurrency],
+) -> Vec<Result<Vec<Badge>, Error>> {
+    futures::stream::iter(accounts.into_iter().map(|id| async move {
+        badge_check::fetch_badges(&id, available_badges)
+            .await
+            .map_err(|err| err.context(format!("The account {} could not be checked", id)))
+    }))
+    .buffered(CONCURRENT_MEMBERS)
+    .collect()
+    .await
+}
+
+impl RosterView {
+    fn view_leaderboard(&self, leaderboard: &Leaderboard) -> Html {
+        let refreshing = !matches!(self.status, WorkFunction::Done | WorkFunction::Err(_));
+        html! {
+            <>
+                <p class="mb-3">
+                    {format!("Updated at {} ", leaderboard.updated_at)}
+                    <button class="button is-small" disabled={refreshing}
+                            onclick={self.link.callback(|_| WorkFunction::Refresh)}>
+                        {"Refresh"}
+                    </button>
+                    <button class="button is-small is-danger is-light ml-1"
+                            onclick={self.link.callback(|_| WorkFunction::Clear)}>
+                        {"Forget roster"}
+                    </button>
+                </p>
+                <table class="table is-fullwidth is-hoverable">
+                    <thead>
+                        <tr>
+                            <th>{"#"}</th>
+                            <th>{"Name"}</th>
+                            <th>{"Badges"}</th>
+                            <th>{"Completed series"}</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                    {
+                        leaderboard.standings.iter().enumerate().map(|(rank, standing)| html! {
+                            <tr>
+                                <td>{rank + 1}</td>
+                                <td title={standing.member.account.clone()}>
+                                    { go_to(Route::Account { id: standing.member.account.clone() }, html! { {&standing.member.name} }, vec![]) }
+                                </td>
+                                <td>{standing.completed}</td>
+                                <td>{standing.series.iter().map(|(series, _)| series.to_string()).collect::<Vec<String>>().join(", ")}</td>
+                            </tr>
+                        }).collect::<Html>()
+                    }
+                    {
+                        leaderboard.failed.iter().map(|(member, reason)| html! {
+                            <tr>
+                                <td>{"—"}</td>
+                                <td title={member.account.clone()}>
+                                    { go_to(Route::Account { id: member.account.clone() }, html! { {&member.name} }, vec![]) }
+                                </td>
+                                <td colspan="2" class="has-text-danger">{reason}</td>
+                            </tr>
+                        }).collect::<Html>()
+                    }
+                    </tbody>
+                </table>
+                { view_first_to_finish(leaderboard) }
+            </>
+        }
+    }
+
+    fn view_members(&self) -> Html {
+        html! {
+            <p>
+                {format!("{} accounts imported. ", self.roster.members.len())}
+                <button class="button is-small" onclick={self.link.callback(|_| WorkFunction::Refresh)}>
+                    {"Build leaderboard"}
+                </button>
+            </p>
+        }
+    }
+
+    fn view_import(&self) -> Html {
+        let text_change = self
+            .link
+            .callback(|e: InputData| WorkFunction::ImportTextChange(e.value));
+        let url_change = self
+            .link
+            .callback(|e: InputData| WorkFunction::ImportUrlChange(e.value));
+        let file_change = self.link.callback(|e: ChangeData| match e {
+            ChangeData::Files(files) => WorkFunction::ImportFile(files.get(0)),
+            _ => WorkFunction::ImportFile(None),
+        });
+        html! {
+            <section class="section">
+                <h2 class="title is-4">{"Import roster"}</h2>
+                <p class="mb-2">
+                    {"One account per line with its display name, separated by a comma, semicolon or tab, e.g. a CSV export of a spreadsheet. Importing replaces the current roster."}
+                </p>
+                <div class="field">
+                    <div class="file">
+                        <label class="file-label">
+                            <input class="file-input" type="file" accept=".csv,.tsv,.txt" onchange={file_change}/>
+                            <span class="file-cta">
+                                <span class="file-label">{"Choose a file..."}</span>
+                            </span>
+                        </label>
+                    </div>
+                </div>
+                <div class="field has-addons">
+                    <div class="control is-expanded">
+                        <input class="input" type="url" placeholder="https://... CSV export" oninput={url_change}/>
+                    </div>
+                    <div class="control">
+                        <button class="button" onclick={self.link.callback(|_| WorkFunction::ImportUrl)}>{"Load"}</button>
+                    </div>
+                </div>
+                <div class="field">
+                    <textarea class="textarea" placeholder="GABC...,Ada Lovelace" value={self.import_text.clone()} oninput={text_change}/>
+                </div>
+                <button class="button is-primary" onclick={self.link.callback(|_| WorkFunction::ImportText)}>{"Import"}</button>
+                {
+                    match &self.import_error {
+                        Some(err) => html! { <p class="help is-danger">{err.report()}</p> },
+                        None => Html::default(),
+                    }
+                }
+            </section>
+        }
+    }
+}
+
+fn view_first_to_finish(leaderboard: &Leaderboard) -> Html {
+    let winners = roster::first_to_finish(leaderboard);
+    if winners.is_empty() {
+        return Html::default();
+    }
+    html! {
+        <>
+            <h2 class="title is-4">{"First to finish"}</h2>
+            <ul>
+            {
+                winners.into_iter().map(|(series, member, date): (usize, Member, String)| html! {
+                    <li><strong>{format!("Series {}: ", series)}</strong>{member.name}{format!(" on {}", date)}</li>
+                }).collect::<Html>()
+            }
+            </ul>
+        </>
+    }
+}
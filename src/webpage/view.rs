@@ -1,4 +1,14 @@
-use crate::webpage::pages::{account::AccountView, home::Home, proof::ProofVerify};
+use crate::util::embed;
+use crate::webpage::pages::{
+    account::AccountView,
+    badge::BadgeDetail,
+    compare::CompareView,
+    embed::{app_base, EmbedView},
+    home::Home,
+    proof::ProofVerify,
+    roster::RosterView,
+};
+use log::error;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
@@ -11,10 +21,20 @@ pub enum Route {
     Account { id: String },
     #[at("/proof/:id")]
     Proof { id: String },
+    #[at("/badge/:account/:code")]
+    Badge { account: String, code: String },
+    #[at("/compare/:ids")]
+    Compare { ids: String },
+    #[at("/roster")]
+    Roster,
+    #[at("/embed/:id")]
+    Embed { id: String },
 }
 
 struct Model {
     link: ComponentLink<Model>,
+    /// shown in an iframe of another site, without navigation and footer
+    embedded: bool,
 }
 
 impl Component for Model {
@@ -23,7 +43,11 @@ impl Component for Model {
 
     fn create(_props: Self::Properties, link: ComponentLink<Self>) -> Self {
         wasm_logger::init(wasm_logger::Config::default());
-        Self { link: link }
+        let embedded = matches!(Route::current_route(), Some(Route::Embed { id: _ }));
+        Self {
+            link: link,
+            embedded: embedded,
+        }
     }
 
     fn update(&mut self, _: Self::Message) -> ShouldRender {
@@ -35,6 +59,9 @@ impl Component for Model {
     }
 
     fn view(&self) -> Html {
+        if self.embedded {
+            return html! { <Router<Route> render={Router::render(switch)}/> };
+        }
         html! {
             <>
 
@@ -75,6 +102,12 @@ fn switch(routes: &Route) -> Html {
         Route::Home => html! {<Home />},
         Route::Account { id } => html! {<AccountView account={id.clone()}/>},
         Route::Proof { id } => html! {<ProofVerify proof={id.clone()}/>},
+        Route::Badge { account, code } => {
+            html! {<BadgeDetail account={account.clone()} code={code.clone()}/>}
+        }
+        Route::Compare { ids } => html! {<CompareView ids={ids.clone()}/>},
+        Route::Roster => html! {<RosterView />},
+        Route::Embed { id } => html! {<EmbedView id={id.clone()}/>},
     }
 }
 
@@ -84,8 +117,34 @@ pub fn go_to(route: Route, html: Html, classes: Vec<&str>) -> Html {
     }
 }
 
+/// Shown in place of the app once it panicked, as the yew app can not recover from a panic.
+/// `{start}` is replaced by the url of the start page.
+const PANIC_SCREEN: &str = r#"
+<div class="sqb-centered" style="display: flex; justify-content: center">
+    <div class="notification is-danger" style="width: auto">
+        <p style="font-size: 1.5rem; font-weight: 500;">Error:</p>
+        <p style="text-align: center; ">Something went wrong while processing your request!</p>
+        <p style="text-align: center; "><a href="{start}">Back to the start page</a></p>
+    </div>
+</div>
+"#;
+
+fn panic_hook(info: &std::panic::PanicInfo) {
+    error!("{}", info);
+    let app = web_sys::window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.query_selector("#app").ok().flatten());
+    if let Some(app) = app {
+        let start = embed::app_url(&app_base(), &Route::Home.to_path());
+        app.set_inner_html(&PANIC_SCREEN.replace("{start}", &start));
+    }
+}
+
 pub fn start() {
+    std::panic::set_hook(Box::new(panic_hook));
     let document = yew::utils::document();
-    let element = document.query_selector("#app").unwrap().unwrap();
-    yew::start_app_in_element::<Model>(element);
+    match document.query_selector("#app") {
+        Ok(Some(element)) => yew::start_app_in_element::<Model>(element),
+        _ => error!("No #app element to mount the app in!"),
+    }
 }
@@ -0,0 +1,51 @@
+// <sqbadge-widget account="G..." size="small|medium|large" series="1,2" theme="light|dark" missing="false">
+// Shows the Stellar Quest badges of an account on any page, through an iframe of the /embed route
+// of the SQBadge instance this script is loaded from, which may be served under a path.
+(function () {
+  var script = document.currentScript;
+  var base = script ? new URL(".", script.src).href : "/";
+  var heights = { small: 120, medium: 192, large: 288 };
+
+  class SqbadgeWidget extends HTMLElement {
+    static get observedAttributes() {
+      return ["account", "size", "series", "theme", "missing"];
+    }
+
+    connectedCallback() {
+      this.render();
+    }
+
+    attributeChangedCallback() {
+      if (this.isConnected) {
+        this.render();
+      }
+    }
+
+    render() {
+      var account = this.getAttribute("account");
+      if (!account) {
+        return;
+      }
+      var params = new URLSearchParams();
+      ["size", "series", "theme", "missing"].forEach(function (name) {
+        if (this.hasAttribute(name)) {
+          params.set(name, this.getAttribute(name));
+        }
+      }, this);
+      var query = params.toString();
+
+      var frame = document.createElement("iframe");
+      frame.src = base + "embed/" + encodeURIComponent(account) + (query ? "?" + query : "");
+      frame.title = "Stellar Quest badges";
+      frame.loading = "lazy";
+      frame.style.border = "0";
+      frame.style.width = "100%";
+      frame.style.height = (heights[this.getAttribute("size")] || heights.medium) + "px";
+      this.replaceChildren(frame);
+    }
+  }
+
+  if (!customElements.get("sqbadge-widget")) {
+    customElements.define("sqbadge-widget", SqbadgeWidget);
+  }
+})();
@@ -0,0 +1,64 @@
+//! Signatures of the `albedo` scheme, which the app checks in rust only, checked by albedos
+//! own verification library loaded from unpkg.
+//!
+//! Run with `tests/run.sh -- --features dev-signer`.
+#![cfg(all(target_arch = "wasm32", feature = "dev-signer"))]
+
+use stellar_badge_wasm::util::local_signer::LocalSigner;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen(inline_js = r#"
+export function load_albedo_verification() {
+    if (typeof albedoSignatureVerification !== "undefined") {
+        return Promise.resolve();
+    }
+    return new Promise((resolve, reject) => {
+        const script = document.createElement("script");
+        script.src = "https://unpkg.com/@albedo-link/signature-verification/lib/albedo.signature.verification.js";
+        script.onload = resolve;
+        script.onerror = () => reject(new Error("albedo verification could not be loaded"));
+        document.head.appendChild(script);
+    });
+}
+
+export function albedo_verify(pubkey, message, signature) {
+    return albedoSignatureVerification.verifyMessageSignature(pubkey, message, signature);
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(catch)]
+    async fn load_albedo_verification() -> Result<JsValue, JsValue>;
+    fn albedo_verify(pubkey: &str, message: &str, signature: &str) -> bool;
+}
+
+/// Same vector as `tests/local_signer.rs` and `tests/proof_encoding.rs`
+const SEED: &str = "SAAACAQDAQCQMBYIBEFAWDANBYHRAEISCMKBKFQXDAMRUGY4DUPB6NKI";
+const ACCOUNT: &str = "GAB2CB576PHBBPQ5ODORRZ2LYCMWPZGWGCN2KDK7DXOIMZASKUY3QZ6Q";
+const MESSAGE: &str = "v1.2.1632146400.test";
+const SIGNATURE: &str = "71dd8271a4dcfdc4648c43d1e339492cd9cbf12138146b7d97e944af6d5b933e99e7eb8e40a8e222d5006015139eceed2c7efa252abbfc1bd58edcfb1dca420e";
+
+#[wasm_bindgen_test]
+async fn albedo_accepts_the_test_vector() {
+    load_albedo_verification().await.unwrap();
+
+    assert!(albedo_verify(ACCOUNT, MESSAGE, SIGNATURE));
+    assert!(!albedo_verify(ACCOUNT, "v1.2.1632146400.other", SIGNATURE));
+}
+
+#[wasm_bindgen_test]
+async fn albedo_accepts_local_signatures() {
+    load_albedo_verification().await.unwrap();
+    let signer = LocalSigner::from_secret(SEED).unwrap();
+
+    for message in vec![MESSAGE, "v2.1ff.1632146400.all01,min3.a:b", "ünïcödé"] {
+        let signed = signer.sign_message(&String::from(message));
+        assert!(
+            albedo_verify(&signed.pubkey, &signed.message, &signed.signature),
+            "{}",
+            message
+        );
+    }
+}
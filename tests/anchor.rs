@@ -0,0 +1,152 @@
+//! Anchor transactions and how their horizon records are matched against proofs.
+#![cfg(not(target_arch = "wasm32"))]
+
+use sha2::{Digest, Sha256};
+use stellar_badge_wasm::stellar::stellar_data::Transaction;
+use stellar_badge_wasm::stellar::transaction::{self, PUBLIC_NETWORK_PASSPHRASE};
+use stellar_badge_wasm::util::anchor::{self, Anchor};
+use stellar_badge_wasm::util::proof_encoding::{SignatureScheme, SignedMessage};
+
+const SIGNER: &str = "GAB2CB576PHBBPQ5ODORRZ2LYCMWPZGWGCN2KDK7DXOIMZASKUY3QZ6Q";
+const OTHER: &str = "GDXSQPNVVG7326K5ZTWGBSHLGYCIHG3RATBMMTXQJSJL6CMSIBDQ5SPD";
+const SIGNED: &str = "v1.2.1632146400.test";
+const TX_HASH: &str = "3389e9f0f1a65f19736cacf544c2e825313e8447f569233bb8db39aa607c8889";
+
+fn signed() -> SignedMessage {
+    SignedMessage {
+        scheme: SignatureScheme::Sep53,
+        network: String::from("public"),
+        pubkey: String::from(SIGNER),
+        message: String::from(SIGNED),
+        signature: String::from("abcd"),
+        anchor: Some(String::from(TX_HASH)),
+        cosigners: vec![],
+    }
+}
+
+fn anchor_tx() -> Transaction {
+    Transaction {
+        hash: String::from(TX_HASH),
+        ledger: 37_000_000,
+        created_at: String::from("2021-09-20T14:00:00Z"),
+        source_account: String::from(SIGNER),
+        successful: true,
+        memo_type: String::from("hash"),
+        memo: Some(base64::encode(Sha256::digest(SIGNED.as_bytes()))),
+        fee_charged: String::from("100"),
+        operation_count: 1,
+    }
+}
+
+#[test]
+fn hashes_the_proof_message() {
+    assert_eq!(
+        anchor::proof_hash(&String::from(SIGNED)).to_vec(),
+        Sha256::digest(SIGNED.as_bytes()).to_vec()
+    );
+}
+
+#[test]
+fn builds_anchor_transactions() {
+    let hash = anchor::proof_hash(&String::from(SIGNED));
+    let envelope = anchor::anchor_transaction(
+        &String::from(SIGNER),
+        &String::from("41"),
+        &String::from(SIGNED),
+    )
+    .unwrap();
+    let raw = base64::decode(&envelope).unwrap();
+
+    // envelope type, source account, fee, sequence, preconditions
+    assert_eq!(raw[..4], 2i32.to_be_bytes());
+    assert_eq!(raw[40..44], 100u32.to_be_bytes());
+    assert_eq!(raw[44..52], 42i64.to_be_bytes());
+    assert_eq!(raw[52..56], 0i32.to_be_bytes());
+    // hash memo
+    assert_eq!(raw[56..60], 3i32.to_be_bytes());
+    assert_eq!(raw[60..92], hash);
+    // a single bump sequence operation to 0
+    assert_eq!(raw[92..96], 1u32.to_be_bytes());
+    assert_eq!(raw[96..100], 0i32.to_be_bytes());
+    assert_eq!(raw[100..104], 11i32.to_be_bytes());
+    assert_eq!(raw[104..112], 0i64.to_be_bytes());
+    // ext, no signatures
+    assert_eq!(raw[112..], [0u8; 8]);
+
+    assert!(anchor::anchor_transaction(
+        &String::from("GABC"),
+        &String::from("41"),
+        &String::from(SIGNED)
+    )
+    .is_err());
+}
+
+#[test]
+fn hashes_transactions() {
+    let envelope = anchor::anchor_transaction(
+        &String::from(SIGNER),
+        &String::from("41"),
+        &String::from(SIGNED),
+    )
+    .unwrap();
+    let raw = base64::decode(&envelope).unwrap();
+
+    let mut base = Sha256::digest(PUBLIC_NETWORK_PASSPHRASE.as_bytes()).to_vec();
+    base.extend_from_slice(&raw[..raw.len() - 4]);
+    assert_eq!(
+        transaction::transaction_hash(&envelope, PUBLIC_NETWORK_PASSPHRASE).unwrap(),
+        hex::encode(Sha256::digest(&base))
+    );
+}
+
+#[test]
+fn confirms_matching_anchors() {
+    assert_eq!(
+        anchor::check(&signed(), &anchor_tx()),
+        Anchor::Confirmed {
+            tx_hash: String::from(TX_HASH),
+            ledger: 37_000_000,
+            closed_at: String::from("2021-09-20T14:00:00Z"),
+        }
+    );
+}
+
+#[test]
+fn rejects_mismatching_anchors() {
+    let mut failed = anchor_tx();
+    failed.successful = false;
+    let mut foreign = anchor_tx();
+    foreign.source_account = String::from(OTHER);
+    let mut text_memo = anchor_tx();
+    text_memo.memo_type = String::from("text");
+    let mut no_memo = anchor_tx();
+    no_memo.memo_type = String::from("none");
+    no_memo.memo = None;
+    let mut other_proof = anchor_tx();
+    other_proof.memo = Some(base64::encode([0u8; 32]));
+
+    for tx in [failed, foreign, text_memo, no_memo, other_proof] {
+        match anchor::check(&signed(), &tx) {
+            Anchor::Mismatch { tx_hash, .. } => assert_eq!(tx_hash, TX_HASH),
+            other => panic!("{:?} accepted as {:?}", tx, other),
+        }
+    }
+}
+
+#[test]
+fn reads_horizon_records() {
+    let tx: Transaction = serde_json::from_value(serde_json::json!({
+        "id": TX_HASH,
+        "hash": TX_HASH,
+        "ledger": 37000000,
+        "created_at": "2021-09-20T14:00:00Z",
+        "source_account": SIGNER,
+        "successful": true,
+        "memo_type": "hash",
+        "memo": base64::encode(Sha256::digest(SIGNED.as_bytes())),
+        "fee_charged": "100",
+        "operation_count": 1,
+    }))
+    .unwrap();
+    assert_eq!(tx, anchor_tx());
+}
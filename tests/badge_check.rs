@@ -0,0 +1,212 @@
+//! Merging the badges of several accounts and evaluating proof predicates against them.
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{badge, BadgeFixture};
+use stellar_badge_wasm::util::badge_check::{self, OwnershipRequirement, Provenance};
+use stellar_badge_wasm::util::proof_encoding::Predicate;
+
+const FIRST: &str = "GAB2CB576PHBBPQ5ODORRZ2LYCMWPZGWGCN2KDK7DXOIMZASKUY3QZ6Q";
+const SECOND: &str = "GDL54NFRPNDJDKTXVOGBPL65IQFQAS6IPVOZVOMRVAUW2AZPVW4GO2TD";
+
+#[test]
+fn merges_the_badges_of_each_account_best_first() {
+    let first = vec![
+        badge("SQ0101", "color", Some(Provenance::IssuerPayment))
+            .held(true)
+            .of(FIRST),
+        badge("SQ0102", "color", None).of(FIRST),
+        badge("SQ0103", "color", Some(Provenance::IssuerPayment)).of(FIRST),
+        badge("SQ0104", "color", Some(Provenance::Unexplained))
+            .held(true)
+            .of(FIRST),
+    ];
+    let second = vec![
+        badge("SQ0101", "color", Some(Provenance::IssuerPayment))
+            .held(true)
+            .of(SECOND),
+        badge("SQ0102", "color", Some(Provenance::IssuerClaimableBalance))
+            .held(true)
+            .of(SECOND),
+        badge("SQ0103", "color", Some(Provenance::IssuerPayment))
+            .held(true)
+            .of(SECOND),
+        badge("SQ0104", "color", None).of(SECOND),
+    ];
+
+    let merged = badge_check::merge_accounts(vec![first, second]);
+    assert_eq!(
+        merged
+            .iter()
+            .map(|b| (b.token.code.as_str(), b.account.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            // ties go to the first account
+            ("SQ0101", FIRST),
+            ("SQ0101", SECOND),
+            ("SQ0102", SECOND),
+            ("SQ0102", FIRST),
+            // earned and held beats earned only
+            ("SQ0103", SECOND),
+            ("SQ0103", FIRST),
+            ("SQ0104", FIRST),
+            ("SQ0104", SECOND),
+        ]
+    );
+    assert!(OwnershipRequirement::EarnedAndHeld.is_met_by(&merged[4]));
+    assert!(!OwnershipRequirement::Earned.is_met_by(&merged[6]));
+}
+
+#[test]
+fn merged_requirements_are_met_by_any_account() {
+    // the first account earned the badge and sent it to the second one
+    let first = vec![badge("SQ0101", "color", Some(Provenance::IssuerPayment)).of(FIRST)];
+    let second = vec![badge(
+        "SQ0101",
+        "color",
+        Some(Provenance::Transferred {
+            from: String::from(FIRST),
+        }),
+    )
+    .held(true)
+    .of(SECOND)];
+
+    let merged = badge_check::merge_accounts(vec![first, second]);
+    for (requirement, met) in [
+        (OwnershipRequirement::Earned, true),
+        (OwnershipRequirement::Held, true),
+        // no single account earned and still holds it
+        (OwnershipRequirement::EarnedAndHeld, false),
+    ] {
+        assert_eq!(
+            badge_check::predicate_met(&Predicate::AtLeast(1), &merged, &requirement),
+            met,
+            "{:?}",
+            requirement
+        );
+    }
+}
+
+#[test]
+fn merges_nothing() {
+    assert_eq!(badge_check::merge_accounts(vec![]), vec![]);
+    let single = vec![badge("SQ0101", "color", None).of(FIRST)];
+    assert_eq!(badge_check::merge_accounts(vec![single.clone()]), single);
+}
+
+#[test]
+fn evaluates_predicates() {
+    let earned = Some(Provenance::IssuerPayment);
+    let badges = vec![
+        badge("SSQ01", "color", None).of(FIRST),
+        badge("SQ0101", "color", earned.clone())
+            .held(true)
+            .of(FIRST),
+        badge("SQ0102", "color", earned.clone()).of(FIRST),
+        badge("SQ0201", "color", earned.clone())
+            .held(true)
+            .of(FIRST),
+        badge("SQ0202", "color", None).of(FIRST),
+        badge(
+            "SQ0301",
+            "color",
+            Some(Provenance::Transferred {
+                from: String::from(SECOND),
+            }),
+        )
+        .held(true)
+        .of(FIRST),
+    ];
+    let vectors = vec![
+        // the series badge itself is not required
+        (
+            Predicate::CompleteSeries(1),
+            OwnershipRequirement::Earned,
+            true,
+        ),
+        (
+            Predicate::CompleteSeries(1),
+            OwnershipRequirement::EarnedAndHeld,
+            false,
+        ),
+        (
+            Predicate::CompleteSeries(2),
+            OwnershipRequirement::Earned,
+            false,
+        ),
+        (
+            Predicate::CompleteSeries(3),
+            OwnershipRequirement::Earned,
+            false,
+        ),
+        (
+            Predicate::CompleteSeries(3),
+            OwnershipRequirement::Held,
+            true,
+        ),
+        // a series without any badge is never complete
+        (
+            Predicate::CompleteSeries(4),
+            OwnershipRequirement::Held,
+            false,
+        ),
+        (Predicate::AtLeast(3), OwnershipRequirement::Earned, true),
+        (Predicate::AtLeast(4), OwnershipRequirement::Earned, false),
+        (Predicate::AtLeast(3), OwnershipRequirement::Held, true),
+        (
+            Predicate::AtLeast(0),
+            OwnershipRequirement::EarnedAndHeld,
+            true,
+        ),
+    ];
+
+    for (predicate, requirement, met) in vectors {
+        assert_eq!(
+            badge_check::predicate_met(&predicate, &badges, &requirement),
+            met,
+            "{:?} {:?}",
+            predicate,
+            requirement
+        );
+    }
+}
+
+#[test]
+fn lists_badges_missing_compared_to_other_accounts() {
+    let earned = Some(Provenance::IssuerPayment);
+    let first = vec![
+        badge("SQ0101", "color", earned.clone())
+            .held(true)
+            .of(FIRST),
+        badge("SQ0101", "color", earned.clone())
+            .held(true)
+            .of(FIRST),
+        badge("SQ0102", "color", None).of(FIRST),
+        badge("SQ0103", "color", earned.clone()).of(FIRST),
+    ];
+    let second = vec![
+        badge("SQ0101", "color", None).of(SECOND),
+        badge("SQ0102", "color", earned.clone())
+            .held(true)
+            .of(SECOND),
+        badge("SQ0103", "color", earned.clone())
+            .held(true)
+            .of(SECOND),
+    ];
+
+    // color and mono variants count once
+    assert_eq!(badge_check::owned_codes(&first), vec!["SQ0101", "SQ0103"]);
+    assert_eq!(
+        badge_check::missing_codes(&first, &[&second]),
+        vec!["SQ0102"]
+    );
+    assert_eq!(
+        badge_check::missing_codes(&second, &[&first]),
+        vec!["SQ0101"]
+    );
+    assert_eq!(
+        badge_check::missing_codes(&first, &[]),
+        Vec::<String>::new()
+    );
+}
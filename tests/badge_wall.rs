@@ -0,0 +1,51 @@
+//! Rendering the owned badges of an account as a single shareable image.
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{badge, earned};
+use std::collections::HashMap;
+use stellar_badge_wasm::util::badge_wall;
+
+#[test]
+fn shows_each_owned_badge_once() {
+    let badges = vec![
+        earned("SQ0102", "mono"),
+        earned("SQ0101", "mono"),
+        earned("SQ0101", "color"),
+        badge("SQ0103", "color", None),
+    ];
+    let wall = badge_wall::wall_badges(&badges);
+    assert_eq!(
+        wall.iter()
+            .map(|b| (b.token.code.as_str(), b.token.tag.as_str()))
+            .collect::<Vec<_>>(),
+        vec![("SQ0101", "color"), ("SQ0102", "mono")]
+    );
+}
+
+#[test]
+fn renders_the_wall() {
+    let badges = vec![
+        earned("SQ0101", "color"),
+        earned("SQ0102", "color"),
+        badge("SQ0103", "color", None),
+    ];
+    let mut images = HashMap::new();
+    images.insert(
+        badges[0].token.image.clone(),
+        String::from("data:image/png;base64,AAAA"),
+    );
+    let svg = badge_wall::render("GABC<&>", &badges, &images);
+
+    assert!(svg.starts_with("<svg") && svg.ends_with("</svg>"));
+    assert!(svg.contains(">2 / 3 Stellar Quest badges<"));
+    assert!(svg.contains(">GABC&lt;&amp;&gt;<"));
+    assert_eq!(svg.matches("<image").count(), 2);
+    assert!(svg.contains(r#"href="data:image/png;base64,AAAA""#));
+    assert!(svg.contains(r#"href="https://quest.stellar.org/badges/SQ0102-color.png""#));
+    assert!(!svg.contains("SQ0103"));
+
+    assert_eq!(badge_wall::image_mime("https://a.b/c.SVG"), "image/svg+xml");
+    assert_eq!(badge_wall::image_mime("https://a.b/c.png"), "image/png");
+}
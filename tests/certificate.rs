@@ -0,0 +1,66 @@
+//! Printable certificates of verified proofs.
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::{earned, BadgeFixture};
+use stellar_badge_wasm::util::certificate::{self, Certificate};
+use stellar_badge_wasm::util::embed;
+use stellar_badge_wasm::util::series::Series;
+
+fn certificate() -> Certificate {
+    Certificate {
+        accounts: vec![String::from("GABC")],
+        badges: vec![
+            earned("SQ0102", "color"),
+            earned("SQ0101", "color").acquired(Some("2021-09-03T10:00:00Z")),
+            earned("SQ0101", "mono").acquired(Some("2021-09-01T10:00:00Z")),
+        ],
+        series: vec![Series {
+            number: 1,
+            ..Series::default()
+        }],
+        // served under the repository name on GitHub Pages
+        verify_url: embed::app_url("https://user.github.io/sqbadge/", "/proof/abc&def"),
+        anchor: Some(String::from("f00d")),
+        issued_at: Utc.timestamp_opt(1633089600, 0).unwrap(),
+    }
+}
+
+#[test]
+fn lists_each_badge_with_its_first_acquisition() {
+    assert_eq!(
+        certificate().entries(),
+        vec![
+            (
+                String::from("SQ0101"),
+                Some(String::from("2021-09-01T10:00:00Z"))
+            ),
+            (String::from("SQ0102"), None),
+        ]
+    );
+}
+
+#[test]
+fn renders_the_certificate() {
+    let svg = certificate().render();
+    assert!(svg.starts_with("<svg") && svg.ends_with("</svg>"));
+    assert!(svg.contains("the following 2 Stellar Quest badges"));
+    assert!(svg.contains(">GABC<"));
+    assert!(svg.contains(">2021-09-01<") && svg.contains(">date unknown<"));
+    assert!(svg.contains(">Completed series 1<"));
+    assert!(svg.contains("Issued 2021-10-01 12:00 UTC"));
+    assert!(svg.contains("Anchored in transaction f00d"));
+    assert!(svg.contains(r#"href="https://user.github.io/sqbadge/proof/abc&amp;def""#));
+    assert!(svg.contains("shape-rendering=\"crispEdges\""));
+}
+
+#[test]
+fn draws_qr_codes() {
+    let qr = certificate::qr_code("https://sqbadge.example/proof/abc", 10, 20, 180).unwrap();
+    assert!(qr.starts_with(r#"<g transform="translate(10 20)"#));
+    assert!(qr.contains("<rect"));
+    // too long for any QR code
+    assert_eq!(certificate::qr_code(&"x".repeat(8000), 0, 0, 180), None);
+}
@@ -0,0 +1,65 @@
+//! Fixture factories shared by the integration tests, `mod common;` in the test file.
+#![allow(dead_code)]
+
+use stellar_badge_wasm::stellar::stellar_data::TOMLCurrency;
+use stellar_badge_wasm::util::badge_check::{Badge, Provenance};
+
+/// Issuer of the color variants
+pub const ISSUER: &str = "GDXSQPNVVG7326K5ZTWGBSHLGYCIHG3RATBMMTXQJSJL6CMSIBDQ5SPD";
+/// Issuer of the mono variants
+pub const MONO_ISSUER: &str = "GDL54NFRPNDJDKTXVOGBPL65IQFQAS6IPVOZVOMRVAUW2AZPVW4GO2TD";
+
+/// The `tag` variant (`color` or `mono`) of the badge `code`
+pub fn currency(code: &str, tag: &str) -> TOMLCurrency {
+    TOMLCurrency {
+        code: String::from(code),
+        issuer: String::from(if tag == "mono" { MONO_ISSUER } else { ISSUER }),
+        image: format!("https://quest.stellar.org/badges/{}-{}.png", code, tag),
+        tag: String::from(tag),
+    }
+}
+
+/// The `tag` variant of the badge `code`, owned when it has a provenance
+pub fn badge(code: &str, tag: &str, provenance: Option<Provenance>) -> Badge {
+    Badge {
+        token: currency(code, tag),
+        owned: provenance.is_some(),
+        provenance,
+        ..Badge::default()
+    }
+}
+
+/// The `tag` variant of the badge `code`, paid out by the issuer
+pub fn earned(code: &str, tag: &str) -> Badge {
+    badge(code, tag, Some(Provenance::IssuerPayment))
+}
+
+/// Adjusts the badges built by `badge`
+pub trait BadgeFixture {
+    /// Whether it is still in the balance of the account
+    fn held(self, held: bool) -> Self;
+    /// Acquired at `date`, an RFC 3339 timestamp
+    fn acquired(self, date: Option<&str>) -> Self;
+    /// Looked up in `account`
+    fn of(self, account: &str) -> Self;
+}
+
+impl BadgeFixture for Badge {
+    fn held(self, held: bool) -> Self {
+        Badge { held, ..self }
+    }
+
+    fn acquired(self, date: Option<&str>) -> Self {
+        Badge {
+            date_accuired: date.map(String::from),
+            ..self
+        }
+    }
+
+    fn of(self, account: &str) -> Self {
+        Badge {
+            account: String::from(account),
+            ..self
+        }
+    }
+}
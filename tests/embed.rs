@@ -0,0 +1,88 @@
+//! Options and snippets of the embeddable badge widget.
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{badge, earned};
+use stellar_badge_wasm::util::embed::{self, EmbedOptions, Size, Theme};
+
+#[test]
+fn reads_the_query_string() {
+    assert_eq!(EmbedOptions::parse(""), EmbedOptions::default());
+    let options = EmbedOptions::parse("?size=small&series=2%2C1,x,2&theme=dark&missing=false");
+    assert_eq!(
+        options,
+        EmbedOptions {
+            size: Size::Small,
+            theme: Theme::Dark,
+            series: vec![1, 2],
+            missing: false,
+        }
+    );
+    assert_eq!(
+        options.query(),
+        "?size=small&series=1,2&theme=dark&missing=false"
+    );
+    assert_eq!(EmbedOptions::parse(&options.query()), options);
+
+    // invalid values keep the defaults
+    assert_eq!(
+        EmbedOptions::parse("size=huge&theme&other=1"),
+        EmbedOptions::default()
+    );
+    assert_eq!(EmbedOptions::default().query(), "");
+}
+
+#[test]
+fn filters_badges() {
+    let options = EmbedOptions::parse("series=1");
+    assert!(options.shows(&badge("SQ0101", "color", None)));
+    assert!(options.shows(&earned("SSQ01", "color")));
+    assert!(!options.shows(&earned("SQ0201", "color")));
+    assert!(!options.shows(&earned("GOLD", "color")));
+
+    let owned = EmbedOptions::parse("missing=false");
+    assert!(owned.shows(&earned("GOLD", "color")));
+    assert!(!owned.shows(&badge("SQ0101", "color", None)));
+}
+
+#[test]
+fn builds_snippets() {
+    let options = EmbedOptions::parse("theme=dark");
+    assert_eq!(
+        embed::embed_url("https://sqbadge.example/", "GABC", &options),
+        "https://sqbadge.example/embed/GABC?theme=dark"
+    );
+    assert!(
+        embed::iframe_snippet("https://sqbadge.example", "GABC", &options)
+            .starts_with(r#"<iframe src="https://sqbadge.example/embed/GABC?theme=dark""#)
+    );
+    assert_eq!(
+        embed::component_snippet("https://sqbadge.example", "GABC", &options),
+        "<script src=\"https://sqbadge.example/sqbadge-widget.js\" defer></script>\n\
+         <sqbadge-widget account=\"GABC\" theme=\"dark\"></sqbadge-widget>"
+    );
+}
+
+#[test]
+fn builds_urls_under_a_path() {
+    // GitHub Pages serves the app under the repository name
+    let base = "https://user.github.io/sqbadge/";
+    assert_eq!(
+        embed::app_url(base, "/account/GABC"),
+        "https://user.github.io/sqbadge/account/GABC"
+    );
+    assert_eq!(
+        embed::app_url("https://sqbadge.example", "/account/GABC"),
+        "https://sqbadge.example/account/GABC"
+    );
+    assert_eq!(
+        embed::embed_url(base, "GABC", &EmbedOptions::default()),
+        "https://user.github.io/sqbadge/embed/GABC"
+    );
+    assert!(
+        embed::component_snippet(base, "GABC", &EmbedOptions::default()).starts_with(
+            "<script src=\"https://user.github.io/sqbadge/sqbadge-widget.js\" defer></script>"
+        )
+    );
+}
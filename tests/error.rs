@@ -0,0 +1,81 @@
+//! User facing messages and codes of `util::error::Error`.
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::error::Error as _;
+use stellar_badge_wasm::util::error::{Error, ProofErr, StellarErr, WalletErr};
+
+#[test]
+fn codes() {
+    let vectors = vec![
+        (
+            Error::from(StellarErr::InvalidPublicKey),
+            "stellar.invalid_public_key",
+        ),
+        (
+            Error::from(StellarErr::AccountNotFound),
+            "stellar.account_not_found",
+        ),
+        (
+            Error::from(StellarErr::NetworkError),
+            "stellar.network_error",
+        ),
+        (
+            Error::from(StellarErr::InvalidResponse),
+            "stellar.invalid_response",
+        ),
+        (
+            Error::from(StellarErr::TransactionFailed),
+            "stellar.transaction_failed",
+        ),
+        (Error::from(StellarErr::Unknown), "stellar.unknown"),
+        (
+            Error::from(ProofErr::ProofInvalidEncoding),
+            "proof.invalid_encoding",
+        ),
+        (
+            Error::from(ProofErr::ProofWrongVersion),
+            "proof.wrong_version",
+        ),
+        (Error::from(WalletErr::NotInstalled), "wallet.not_installed"),
+        (
+            Error::from(WalletErr::Rejected(String::from("declined"))),
+            "wallet.rejected",
+        ),
+        (
+            Error::from(WalletErr::InvalidResponse),
+            "wallet.invalid_response",
+        ),
+        (Error::Other(String::from("message")), "other"),
+        (Error::Unknown, "unknown"),
+    ];
+
+    for (err, code) in vectors {
+        assert_eq!(err.code(), code, "{:?}", err);
+        assert!(!err.to_string().is_empty(), "{:?}", err);
+    }
+}
+
+#[test]
+fn context_keeps_the_cause() {
+    let err = Error::from(StellarErr::AccountNotFound)
+        .context("Loading GABC")
+        .context("Checking the proof");
+
+    assert_eq!(err.code(), "stellar.account_not_found");
+    assert_eq!(err.root(), &Error::StellarErr(StellarErr::AccountNotFound));
+    assert_eq!(
+        err.report(),
+        "Checking the proof: Loading GABC: The account could not be found on the stellar network!"
+    );
+
+    // every error of the chain only prints itself, so walking the sources repeats nothing
+    assert_eq!(err.to_string(), "Checking the proof");
+    let source = err.source().expect("context has a source");
+    assert_eq!(source.to_string(), "Loading GABC");
+    let root = source.source().expect("context has a source");
+    assert_eq!(
+        root.to_string(),
+        "The account could not be found on the stellar network!"
+    );
+    assert!(root.source().is_none());
+}
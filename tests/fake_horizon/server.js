@@ -0,0 +1,125 @@
+// Minimal fake horizon serving the recorded fixtures in tests/fixtures.
+//
+//   node tests/fake_horizon/server.js
+//
+// PORT (default 8001), PAGE_SIZE (default 200) and FIXTURES can be set through the environment.
+// A small PAGE_SIZE forces the clients through the `_links.next` pagination.
+
+const http = require("http");
+const fs = require("fs");
+const path = require("path");
+
+const PORT = parseInt(process.env.PORT || "8001");
+const PAGE_SIZE = parseInt(process.env.PAGE_SIZE || "200");
+const FIXTURES = process.env.FIXTURES || path.join(__dirname, "..", "fixtures");
+const BASE = `http://127.0.0.1:${PORT}/`;
+
+const PUBLIC_KEY = /^G[A-Z2-7]{55}$/;
+
+function fixture(...parts) {
+    const file = path.join(FIXTURES, ...parts);
+    if (!fs.existsSync(file)) {
+        return null;
+    }
+    return fs.readFileSync(file, "utf8");
+}
+
+function problem(status, title, detail) {
+    return [status, {
+        type: `https://stellar.org/horizon-errors/${status == 404 ? "not_found" : "bad_request"}`,
+        title: title,
+        status: status,
+        detail: detail,
+    }];
+}
+
+// Pages through a recorded list of records the same way horizon does (paging_token cursors)
+function page(url, records) {
+    const order = url.searchParams.get("order") || "asc";
+    const limit = Math.min(parseInt(url.searchParams.get("limit") || "10"), PAGE_SIZE);
+    const cursor = url.searchParams.get("cursor");
+
+    let sorted = records.slice();
+    if (order == "desc") {
+        sorted.reverse();
+    }
+    let start = 0;
+    if (cursor) {
+        start = sorted.findIndex((r) => r.paging_token == cursor) + 1;
+        if (start == 0) {
+            start = sorted.length;
+        }
+    }
+    const slice = sorted.slice(start, start + limit);
+    const last = slice.length > 0 ? slice[slice.length - 1].paging_token : cursor || "";
+
+    const link = (c, o) => {
+        const next = new URL(url.href);
+        next.searchParams.set("cursor", c);
+        next.searchParams.set("order", o);
+        next.searchParams.set("limit", limit);
+        return { href: BASE + next.pathname.slice(1) + next.search };
+    };
+
+    return [200, {
+        _links: {
+            self: { href: BASE + url.pathname.slice(1) + url.search },
+            next: link(last, order),
+            prev: link(slice.length > 0 ? slice[0].paging_token : last, order == "asc" ? "desc" : "asc"),
+        },
+        _embedded: { records: slice },
+    }];
+}
+
+function records(kind, id) {
+    const data = fixture(kind, `${id}.json`);
+    return data ? JSON.parse(data) : [];
+}
+
+function route(url) {
+    const parts = url.pathname.split("/").filter((p) => p.length > 0);
+
+    if (parts[0] == ".well-known" && parts[1] == "stellar.toml") {
+        const toml = fixture("stellar.toml");
+        return toml ? [200, toml] : problem(404, "Resource Missing", "no stellar.toml recorded");
+    }
+
+    if (parts[0] == "accounts" && parts.length >= 2) {
+        const id = parts[1];
+        if (!PUBLIC_KEY.test(id)) {
+            return problem(400, "Bad Request", "The request you sent was invalid in some way.");
+        }
+        const account = fixture("accounts", `${id}.json`);
+        if (parts.length == 2 && account) {
+            return [200, JSON.parse(account)];
+        }
+        // issuers only have their history recorded
+        const history = parts[2] == "payments" || parts[2] == "operations";
+        if (history && (account || fixture(parts[2], `${id}.json`))) {
+            return page(url, records(parts[2], id));
+        }
+    }
+
+    if (parts[0] == "claimable_balances") {
+        const claimant = url.searchParams.get("claimant") || "";
+        if (!PUBLIC_KEY.test(claimant)) {
+            return problem(400, "Bad Request", "The request you sent was invalid in some way.");
+        }
+        return page(url, records("claimable_balances", claimant));
+    }
+
+    return problem(404, "Resource Missing", "The resource at the url requested was not found.");
+}
+
+http.createServer((req, res) => {
+    const url = new URL(req.url, BASE);
+    const [status, body] = route(url);
+    const text = typeof body == "string" ? body : JSON.stringify(body);
+
+    res.writeHead(status, {
+        "Access-Control-Allow-Origin": "*",
+        "Content-Type": typeof body == "string" ? "text/plain" : "application/hal+json",
+    });
+    res.end(text);
+    console.log(`${status} ${req.method} ${req.url}`);
+}).listen(PORT, () => console.log(`fake horizon listening on ${BASE}`));
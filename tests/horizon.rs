@@ -0,0 +1,275 @@
+//! Integration tests against the fake horizon in `tests/fake_horizon`.
+//!
+//! Run with `tests/run.sh`, which starts the fake horizon with a small page size
+//! and runs `wasm-pack test --headless --firefox`.
+#![cfg(target_arch = "wasm32")]
+
+use stellar_badge_wasm::stellar::stellar;
+use stellar_badge_wasm::stellar::stellar_data::TOMLCurrency;
+use stellar_badge_wasm::util::badge_check::{self, Badge, OwnershipRequirement, Provenance};
+use stellar_badge_wasm::util::error::{Error, StellarErr};
+use stellar_badge_wasm::util::proof_encoding::Proof;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const LEARNER: &str = "GAJBB4IMGQJJST4JZY3YFCOX77NL6CVFSS27HQAQ74YBUHJQT3SCDTF4";
+const EMPTY: &str = "GAXBZ6UCWA24E3F3XWXGGLHKA4CRJ24LO47WC2VOV5TI4LYL5DYQ3OYV";
+const UNKNOWN: &str = "GCZDU2UEHHAN3ZKRLCJ6PSIMDYZDHODBNZRUI4HSBXCJFC6PGYE3ZKNU";
+const FRIEND: &str = "GDG6JBJXZIWCQCCP6VQIE3IOMOELPRL2KFEXU3FVN44XFCPFF72BWXMH";
+/// Earned SQ0101, sent it to FRIEND and removed the trustline, still holds SQ0201
+const DROPPED: &str = "GCRGE24PIEEXQH5YRBCMN2LAEOC7DIBOTNWRARAQ6VHFHE64DRFIMYRT";
+
+fn fake_horizon() -> String {
+    let endpoint = option_env!("FAKE_HORIZON").unwrap_or("http://127.0.0.1:8001/");
+    stellar::set_horizon_endpoint(endpoint);
+    String::from(endpoint)
+}
+
+async fn catalog() -> Vec<TOMLCurrency> {
+    let toml = format!("{}.well-known/stellar.toml", fake_horizon());
+    stellar::fetch_toml_currencies(&toml)
+        .await
+        .expect("fake horizon serves a stellar.toml")
+}
+
+fn badge<'a>(badges: &'a Vec<Badge>, code: &str, tag: &str) -> &'a Badge {
+    badges
+        .iter()
+        .find(|b| b.token.code == code && b.token.tag == tag)
+        .expect("badge is in catalog")
+}
+
+#[wasm_bindgen_test]
+async fn reads_catalog() {
+    let catalog = catalog().await;
+    assert_eq!(catalog.len(), 7);
+    assert_eq!(catalog.iter().filter(|c| c.tag == "mono").count(), 1);
+}
+
+#[wasm_bindgen_test]
+async fn reads_currency_metadata() {
+    let toml = format!("{}.well-known/stellar.toml", fake_horizon());
+    let issuer = String::from("GDL54NFRPNDJDKTXVOGBPL65IQFQAS6IPVOZVOMRVAUW2AZPVW4GO2TD");
+    let metadata = stellar::fetch_toml_currency_metadata(&toml, &String::from("SQ0101"), &issuer)
+        .await
+        .expect("SQ0101 mono is in the catalog");
+    assert!(metadata.contains(&(String::from("issuer"), issuer.clone())));
+    assert!(metadata.contains(&(String::from("tag"), String::from("mono"))));
+
+    let missing =
+        stellar::fetch_toml_currency_metadata(&toml, &String::from("SQ0102"), &issuer).await;
+    assert_eq!(missing, None);
+}
+
+#[wasm_bindgen_test]
+async fn pages_through_payments() {
+    fake_horizon();
+    let payments = stellar::fetch_account_payments(&LEARNER.to_string())
+        .await
+        .unwrap();
+
+    // 7 recorded payments, served 2 per page
+    assert_eq!(payments.len(), 7);
+    assert!(payments.windows(2).all(|p| p[0].id < p[1].id));
+}
+
+#[wasm_bindgen_test]
+async fn pages_only_through_badge_payments() {
+    let catalog = catalog().await;
+    let assets = catalog
+        .iter()
+        .filter(|c| c.tag != "mono")
+        .map(|c| c.asset())
+        .collect::<Vec<String>>();
+    let trusted = assets
+        .iter()
+        .filter(|a| !a.starts_with("SQ0202") && !a.starts_with("SSQ01"))
+        .cloned()
+        .collect();
+
+    let payments =
+        stellar::fetch_account_payments_for_assets(&LEARNER.to_string(), &trusted, &assets)
+            .await
+            .unwrap();
+
+    assert_eq!(payments.len(), 4);
+    assert!(payments.iter().all(|p| p.asset_type == "credit_alphanum12"));
+    assert!(payments.windows(2).all(|p| p[0].id < p[1].id));
+}
+
+#[wasm_bindgen_test]
+async fn stops_at_the_oldest_trustline() {
+    let catalog = catalog().await;
+    let assets = catalog.iter().map(|c| c.asset()).collect::<Vec<String>>();
+    let sq0201 = assets
+        .iter()
+        .filter(|a| a.starts_with("SQ0201"))
+        .cloned()
+        .collect();
+
+    // the SQ0201 trustline was created after the other badges had been paid out
+    let payments =
+        stellar::fetch_account_payments_for_assets(&LEARNER.to_string(), &sq0201, &assets)
+            .await
+            .unwrap();
+
+    let codes = payments
+        .iter()
+        .map(|p| p.asset_code.as_str())
+        .collect::<Vec<&str>>();
+    assert_eq!(codes, vec!["SQ0201", "SQ0103"]);
+}
+
+#[wasm_bindgen_test]
+async fn resolves_badges() {
+    let catalog = catalog().await;
+    let badges = badge_check::fetch_badges(&LEARNER.to_string(), &catalog)
+        .await
+        .unwrap();
+    assert_eq!(badges.len(), catalog.len());
+
+    let paid = badge(&badges, "SQ0101", "color");
+    assert!(paid.owned && paid.held);
+    assert_eq!(paid.provenance, Some(Provenance::IssuerPayment));
+
+    let claimed = badge(&badges, "SQ0102", "color");
+    assert!(claimed.owned && claimed.held);
+    assert_eq!(claimed.provenance, Some(Provenance::IssuerClaimableBalance));
+    assert_eq!(claimed.tx_hash, Some("c4".repeat(32)));
+
+    let sent_away = badge(&badges, "SQ0103", "color");
+    assert!(sent_away.owned && !sent_away.held);
+    assert_eq!(sent_away.sent_to, Some(FRIEND.to_string()));
+
+    let transferred = badge(&badges, "SQ0201", "color");
+    assert!(transferred.owned && !transferred.is_earned());
+    assert_eq!(
+        transferred.provenance,
+        Some(Provenance::Transferred {
+            from: FRIEND.to_string()
+        })
+    );
+
+    let pending = badge(&badges, "SQ0202", "color");
+    assert!(!pending.owned && pending.pending);
+    assert!(pending.claimable_balance_id.is_some());
+
+    assert!(!badge(&badges, "SSQ01", "color").owned);
+    assert!(!badge(&badges, "SQ0101", "mono").owned);
+}
+
+#[wasm_bindgen_test]
+async fn resolves_badges_with_full_scan() {
+    let catalog = catalog().await;
+    let options = badge_check::ResolveOptions {
+        concurrency: 1,
+        full_scan: true,
+        ..badge_check::ResolveOptions::default()
+    };
+    for account in vec![LEARNER, DROPPED] {
+        let full = badge_check::fetch_badges_with(&account.to_string(), &catalog, &options)
+            .await
+            .unwrap();
+        let filtered = badge_check::fetch_badges(&account.to_string(), &catalog)
+            .await
+            .unwrap();
+
+        assert_eq!(full, filtered, "{}", account);
+    }
+}
+
+#[wasm_bindgen_test]
+async fn resolves_badges_with_removed_trustline() {
+    let catalog = catalog().await;
+    let badges = badge_check::fetch_badges(&DROPPED.to_string(), &catalog)
+        .await
+        .unwrap();
+
+    let dropped = badge(&badges, "SQ0101", "color");
+    assert!(dropped.owned && !dropped.held);
+    assert_eq!(dropped.provenance, Some(Provenance::IssuerPayment));
+    assert_eq!(dropped.sent_to, Some(FRIEND.to_string()));
+    assert!(OwnershipRequirement::Earned.is_met_by(dropped));
+    assert!(badge(&badges, "SQ0201", "color").held);
+
+    // requesting an asset without trustline reads the complete history
+    let options = badge_check::ResolveOptions {
+        assets: vec![dropped.token.asset()],
+        ..badge_check::ResolveOptions::default()
+    };
+    let requested = badge_check::fetch_badges_with(&DROPPED.to_string(), &catalog, &options)
+        .await
+        .unwrap();
+    assert_eq!(requested, badges);
+}
+
+#[wasm_bindgen_test]
+async fn account_without_badges() {
+    let catalog = catalog().await;
+    let badges = badge_check::fetch_badges(&EMPTY.to_string(), &catalog)
+        .await
+        .unwrap();
+
+    assert!(badges.iter().all(|b| !b.owned && !b.pending));
+}
+
+#[wasm_bindgen_test]
+async fn invalid_public_key() {
+    let catalog = catalog().await;
+    let err = badge_check::fetch_badges(&"invalid".to_string(), &catalog).await;
+
+    assert_eq!(err, Err(Error::StellarErr(StellarErr::InvalidPublicKey)));
+}
+
+#[wasm_bindgen_test]
+async fn account_not_found() {
+    let catalog = catalog().await;
+    let err = badge_check::fetch_badges(&UNKNOWN.to_string(), &catalog).await;
+
+    assert_eq!(err, Err(Error::StellarErr(StellarErr::AccountNotFound)));
+}
+
+#[wasm_bindgen_test]
+async fn verifies_proof_against_account() {
+    let catalog = catalog().await;
+    let badges = badge_check::fetch_badges(&LEARNER.to_string(), &catalog)
+        .await
+        .unwrap();
+
+    let mut proof = Proof::default();
+    proof.timestamp = Some(1632146400);
+    proof.unique_id = Some(String::from("integration"));
+    proof.owned_badges = badges
+        .iter()
+        .filter(|b| b.is_earned())
+        .map(|b| b.token.clone())
+        .collect();
+
+    let encoded = proof.encode_v1().unwrap();
+    let decoded = Proof::decode_v1(&encoded, &catalog).unwrap();
+    assert_eq!(decoded.timestamp, proof.timestamp);
+    assert_eq!(decoded.unique_id, proof.unique_id);
+
+    let claims_met = |requirement: OwnershipRequirement| {
+        decoded.owned_badges.iter().all(|claimed| {
+            badges
+                .iter()
+                .filter(|b| b.token.code == claimed.code)
+                .any(|b| requirement.is_met_by(b))
+        })
+    };
+    assert!(claims_met(OwnershipRequirement::Earned));
+    // SQ0103 has been sent away since
+    assert!(!claims_met(OwnershipRequirement::EarnedAndHeld));
+
+    let mut forged = proof.clone();
+    forged
+        .owned_badges
+        .push(badge(&badges, "SSQ01", "color").token.clone());
+    let forged = Proof::decode_v1(&forged.encode_v1().unwrap(), &catalog).unwrap();
+    assert!(forged.owned_badges.iter().any(|claimed| !badges
+        .iter()
+        .filter(|b| b.token.code == claimed.code)
+        .any(|b| OwnershipRequirement::Earned.is_met_by(b))));
+}
@@ -0,0 +1,170 @@
+//! Deterministic proofs signed by the `dev-signer` local key.
+//!
+//! Run with `cargo test --features dev-signer`.
+#![cfg(all(feature = "dev-signer", not(target_arch = "wasm32")))]
+
+use ed25519_dalek::{PublicKey, Signature, Verifier};
+use sha2::{Digest, Sha256};
+use stellar_badge_wasm::stellar::transaction::PUBLIC_NETWORK_PASSPHRASE;
+use stellar_badge_wasm::stellar::{strkey, transaction};
+use stellar_badge_wasm::util::local_signer::LocalSigner;
+use stellar_badge_wasm::util::proof_encoding::{self, Cosignature, SignatureScheme, SignedMessage};
+
+/// Seed bytes 0x00..0x1f
+const SEED: &str = "SAAACAQDAQCQMBYIBEFAWDANBYHRAEISCMKBKFQXDAMRUGY4DUPB6NKI";
+const ACCOUNT: &str = "GAB2CB576PHBBPQ5ODORRZ2LYCMWPZGWGCN2KDK7DXOIMZASKUY3QZ6Q";
+const MESSAGE: &str = "v1.2.1632146400.test";
+/// ed25519 signature of `ACCOUNT:MESSAGE`, accepted by albedos verification library (`tests/albedo.rs`)
+const SIGNATURE: &str = "71dd8271a4dcfdc4648c43d1e339492cd9cbf12138146b7d97e944af6d5b933e99e7eb8e40a8e222d5006015139eceed2c7efa252abbfc1bd58edcfb1dca420e";
+
+fn signer() -> LocalSigner {
+    LocalSigner::from_secret(SEED).unwrap()
+}
+
+fn public_key() -> PublicKey {
+    PublicKey::from_bytes(&strkey::decode(strkey::VERSION_ACCOUNT_ID, ACCOUNT).unwrap()).unwrap()
+}
+
+#[test]
+fn derives_account_from_seed() {
+    assert_eq!(signer().account(), ACCOUNT);
+}
+
+#[test]
+fn rejects_invalid_seeds() {
+    let invalid = vec![
+        "",
+        ACCOUNT,
+        // checksum broken
+        "SAAACAQDAQCQMBYIBEFAWDANBYHRAEISCMKBKFQXDAMRUGY4DUPB6NKA",
+        "SAAACAQDAQCQMBYIBEFAWDANBYHRAEISCMKBKFQXDAMRUGY4DUPB6NK",
+    ];
+    for seed in invalid {
+        assert!(LocalSigner::from_secret(seed).is_err(), "{}", seed);
+    }
+}
+
+#[test]
+fn signs_like_albedo() {
+    let signed = signer().sign_message(&String::from(MESSAGE));
+    assert_eq!(signed.pubkey, ACCOUNT);
+    assert_eq!(signed.message, MESSAGE);
+    assert_eq!(signed.signature, SIGNATURE);
+
+    let (signature, pub_key, message) =
+        proof_encoding::parse_albedo_signed_message(&signed.encode_legacy()).unwrap();
+    assert_eq!(
+        (signature.as_str(), pub_key.as_str(), message.as_str()),
+        (SIGNATURE, ACCOUNT, MESSAGE)
+    );
+
+    assert_eq!(SignedMessage::parse(&signed.encode()), Ok(signed));
+
+    let signature = Signature::from_bytes(&hex::decode(signature).unwrap()).unwrap();
+    let signed_message = format!("{}:{}", ACCOUNT, MESSAGE);
+    assert!(public_key()
+        .verify(signed_message.as_bytes(), &signature)
+        .is_ok());
+}
+
+#[test]
+fn signs_sep53_messages() {
+    // example of the SEP-53 specification
+    let signer =
+        LocalSigner::from_secret("SAKICEVQLYWGSOJS4WW7HZJWAHZVEEBS527LHK5V4MLJALYKICQCJXMW")
+            .unwrap();
+    assert_eq!(
+        signer.account(),
+        "GBXFXNDLV4LSWA4VB7YIL5GBD7BVNR22SGBTDKMO2SBZZHDXSKZYCP7L"
+    );
+    let signed = signer.sign_message_with(SignatureScheme::Sep53, &String::from("Hello, World!"));
+    assert_eq!(signed.scheme, SignatureScheme::Sep53);
+    assert_eq!(
+        base64::encode(hex::decode(&signed.signature).unwrap()),
+        "fO5dbYhXUhBMhe6kId/cuVq/AfEnHRHEvsP8vXh03M1uLpi5e46yO2Q8rEBzu3feXQewcQE5GArp88u6ePK6BA=="
+    );
+    assert_eq!(signed.verify(), Ok(true));
+}
+
+#[test]
+fn verifies_cosigned_messages() {
+    let cosigner =
+        LocalSigner::from_secret(&strkey::encode(strkey::VERSION_SEED, &[7u8; 32])).unwrap();
+    let accounts = vec![signer().account().clone(), cosigner.account().clone()];
+    let text = proof_encoding::signed_text(MESSAGE, &accounts);
+    let mut signed = signer().sign_message(&text);
+    signed.message = String::from(MESSAGE);
+    let cosigned = cosigner.sign_message_with(SignatureScheme::Sep53, &text);
+    signed.cosigners.push(Cosignature {
+        scheme: cosigned.scheme,
+        pubkey: cosigned.pubkey,
+        signature: cosigned.signature,
+    });
+    assert_eq!(signed.signed_text(), text);
+    assert_eq!(signed.verify(), Ok(true));
+    assert_eq!(SignedMessage::parse(&signed.encode()), Ok(signed.clone()));
+
+    // the cosignature has to cover the same message
+    let mut moved = signed.clone();
+    let other = cosigner.sign_message_with(SignatureScheme::Sep53, &String::from("v1.0.0.other"));
+    moved.cosigners[0].signature = other.signature;
+    assert_eq!(moved.verify(), Ok(false));
+
+    // and the same accounts, a cosignature of another proof of the message does not count
+    let third =
+        LocalSigner::from_secret(&strkey::encode(strkey::VERSION_SEED, &[9u8; 32])).unwrap();
+    let lifted = proof_encoding::signed_text(
+        MESSAGE,
+        &[third.account().clone(), cosigner.account().clone()],
+    );
+    let mut moved = signed.clone();
+    moved.cosigners[0].signature = cosigner
+        .sign_message_with(SignatureScheme::Sep53, &lifted)
+        .signature;
+    assert_eq!(moved.verify(), Ok(false));
+
+    // the signature of the account alone does not cover the cosigners either
+    let mut alone = signer().sign_message(&String::from(MESSAGE));
+    alone.cosigners = signed.cosigners.clone();
+    assert_eq!(alone.verify(), Ok(false));
+}
+
+#[test]
+fn signs_transactions() {
+    let envelope = transaction::claim_claimable_balance(
+        &String::from(ACCOUNT),
+        &String::from("100"),
+        &format!("00000000{}", "ab".repeat(32)),
+        &String::from("SQ0101"),
+        &String::from("GDXSQPNVVG7326K5ZTWGBSHLGYCIHG3RATBMMTXQJSJL6CMSIBDQ5SPD"),
+        true,
+    )
+    .unwrap();
+    let signed = signer()
+        .sign_transaction(&envelope, PUBLIC_NETWORK_PASSPHRASE)
+        .unwrap();
+
+    let unsigned = base64::decode(&envelope).unwrap();
+    let signed = base64::decode(&signed).unwrap();
+    let tx_end = unsigned.len() - 4;
+    assert_eq!(signed[..tx_end], unsigned[..tx_end]);
+    // one signature: count, hint, length prefixed signature
+    assert_eq!(signed.len(), unsigned.len() + 4 + 4 + 64);
+    assert_eq!(signed[tx_end..tx_end + 4], 1u32.to_be_bytes());
+    assert_eq!(
+        signed[tx_end + 4..tx_end + 8],
+        public_key().as_bytes()[28..]
+    );
+
+    let mut base = Sha256::digest(PUBLIC_NETWORK_PASSPHRASE.as_bytes()).to_vec();
+    base.extend_from_slice(&unsigned[..tx_end]);
+    let signature = Signature::from_bytes(&signed[tx_end + 12..]).unwrap();
+    assert!(public_key()
+        .verify(&Sha256::digest(&base), &signature)
+        .is_ok());
+
+    // only unsigned envelopes can be signed
+    assert!(signer()
+        .sign_transaction(&base64::encode(&signed), PUBLIC_NETWORK_PASSPHRASE)
+        .is_err());
+}
@@ -0,0 +1,558 @@
+//! Golden vectors and round trip properties of the proof codec.
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{currency, ISSUER};
+use proptest::prelude::*;
+use stellar_badge_wasm::stellar::stellar_data::TOMLCurrency;
+use stellar_badge_wasm::util::error::{Error, ProofErr, StellarErr};
+use stellar_badge_wasm::util::proof_encoding::{
+    self, Cosignature, Predicate, Proof, SignatureScheme, SignedMessage,
+};
+
+fn code(series: usize, quest: usize) -> String {
+    match quest {
+        0 => format!("SSQ{:02}", series),
+        _ => format!("SQ{:02}{:02}", series, quest),
+    }
+}
+
+/// Every quest of the first `series` series, with mono variants for even quests
+fn catalog(series: usize) -> Vec<TOMLCurrency> {
+    (1..=series)
+        .flat_map(|s| (0..9).map(move |q| (s, q)))
+        .flat_map(|(s, q)| {
+            let mut currencies = vec![currency(&code(s, q), "color")];
+            if q % 2 == 0 {
+                currencies.push(currency(&code(s, q), "mono"));
+            }
+            currencies
+        })
+        .collect()
+}
+
+fn proof(codes: &[&str], timestamp: Option<i64>, unique_id: Option<&str>) -> Proof {
+    Proof {
+        owned_badges: codes.iter().map(|c| currency(c, "color")).collect(),
+        timestamp,
+        unique_id: unique_id.map(String::from),
+        predicates: vec![],
+    }
+}
+
+fn codes(proof: &Proof) -> Vec<String> {
+    let mut codes = proof
+        .owned_badges
+        .iter()
+        .map(|b| b.code.clone())
+        .collect::<Vec<String>>();
+    codes.sort();
+    codes.dedup();
+    codes
+}
+
+#[test]
+fn golden_encodings() {
+    let vectors: Vec<(Proof, &str)> = vec![
+        (
+            proof(&["SQ0101"], Some(1632146400), Some("hello")),
+            "v1.2.1632146400.hello",
+        ),
+        (
+            proof(
+                &[
+                    "SSQ01", "SQ0101", "SQ0102", "SQ0103", "SQ0104", "SQ0105", "SQ0106", "SQ0107",
+                    "SQ0108",
+                ],
+                Some(0),
+                None,
+            ),
+            "v1.1ff.0.",
+        ),
+        (proof(&["SQ0108"], None, None), "v1.100.."),
+        (proof(&["SQ0201"], None, None), "v1.400.."),
+        (proof(&["SQ0101", "SQ0301"], None, Some("a")), "v1.80002..a"),
+        (proof(&["SQ0801"], None, None), "v1.10000000000000000.."),
+        (proof(&[], None, None), "v1.0.."),
+    ];
+
+    for (proof, encoded) in vectors {
+        assert_eq!(proof.encode_v1().unwrap(), encoded);
+    }
+}
+
+/// Encoded proof, its badge codes, timestamp and unique id
+type Decoding<'a> = (&'a str, Vec<&'a str>, Option<i64>, Option<&'a str>);
+
+#[test]
+fn golden_decodings() {
+    let catalog = catalog(9);
+    let vectors: Vec<Decoding> = vec![
+        // created before the codec supported more than 7 series
+        (
+            "v1.80002.1632146400.test",
+            vec!["SQ0101", "SQ0301"],
+            Some(1632146400),
+            Some("test"),
+        ),
+        (
+            "v1.1ff.0.",
+            vec![
+                "SQ0101", "SQ0102", "SQ0103", "SQ0104", "SQ0105", "SQ0106", "SQ0107", "SQ0108",
+                "SSQ01",
+            ],
+            Some(0),
+            None,
+        ),
+        ("v1.10000000000000000..", vec!["SQ0801"], None, None),
+        ("v1.0002..", vec!["SQ0101"], None, None),
+        ("v1...", vec![], None, None),
+        // dots in the message are kept
+        (
+            "v1.2..hello.world",
+            vec!["SQ0101"],
+            None,
+            Some("hello.world"),
+        ),
+    ];
+
+    for (encoded, badges, timestamp, unique_id) in vectors {
+        let decoded = Proof::decode_v1(&String::from(encoded), &catalog).unwrap();
+        assert_eq!(codes(&decoded), badges, "{}", encoded);
+        assert_eq!(decoded.timestamp, timestamp, "{}", encoded);
+        assert_eq!(decoded.unique_id.as_deref(), unique_id, "{}", encoded);
+    }
+}
+
+#[test]
+fn rejects_invalid_encodings() {
+    let catalog = catalog(2);
+    let vectors = vec![
+        ("", Error::ProofErr(ProofErr::ProofInvalidEncoding)),
+        ("v1.2.", Error::ProofErr(ProofErr::ProofInvalidEncoding)),
+        ("v2.2..", Error::ProofErr(ProofErr::ProofWrongVersion)),
+        ("v1.xyz..", Error::ProofErr(ProofErr::ProofInvalidEncoding)),
+        ("v1.ä..", Error::ProofErr(ProofErr::ProofInvalidEncoding)),
+    ];
+
+    for (encoded, err) in vectors {
+        assert_eq!(
+            Proof::decode_v1(&String::from(encoded), &catalog),
+            Err(err),
+            "{}",
+            encoded
+        );
+    }
+}
+
+#[test]
+fn skips_badges_that_can_not_be_encoded() {
+    let proof = proof(
+        &["SQ0109", "SQ0001", "SQ01", "XLM", "SQ01ä", "SSQ1"],
+        None,
+        None,
+    );
+    assert_eq!(proof.encode_v1().unwrap(), "v1.0..");
+}
+
+#[test]
+fn encodes_predicates_as_v2() {
+    let mut with_predicates = proof(&["SQ0101"], Some(1632146400), Some("a.b"));
+    with_predicates.predicates = vec![Predicate::CompleteSeries(3), Predicate::AtLeast(10)];
+    let encoded = with_predicates.encode().unwrap();
+    assert_eq!(encoded, "v2.2.1632146400.all03,min10.a.b");
+
+    let decoded = Proof::decode(&encoded, &catalog(3)).unwrap();
+    assert_eq!(codes(&decoded), vec!["SQ0101"]);
+    assert_eq!(decoded.predicates, with_predicates.predicates);
+    assert_eq!(decoded.unique_id.as_deref(), Some("a.b"));
+
+    // proofs without predicates stay v1
+    let plain = proof(&["SQ0101"], None, None);
+    assert_eq!(plain.encode().unwrap(), plain.encode_v1().unwrap());
+    assert_eq!(
+        codes(&Proof::decode(&plain.encode().unwrap(), &catalog(3)).unwrap()),
+        vec!["SQ0101"]
+    );
+}
+
+#[test]
+fn rejects_invalid_predicates() {
+    let catalog = catalog(2);
+    let vectors = vec![
+        ("v2.2..", Error::ProofErr(ProofErr::ProofInvalidEncoding)),
+        (
+            "v2.2..all00.",
+            Error::ProofErr(ProofErr::ProofInvalidEncoding),
+        ),
+        (
+            "v2.2..min.",
+            Error::ProofErr(ProofErr::ProofInvalidEncoding),
+        ),
+        (
+            "v2.2..max3.",
+            Error::ProofErr(ProofErr::ProofInvalidEncoding),
+        ),
+        (
+            "v2.2..min-1.",
+            Error::ProofErr(ProofErr::ProofInvalidEncoding),
+        ),
+        ("v3.2..min1.", Error::ProofErr(ProofErr::ProofWrongVersion)),
+    ];
+
+    for (encoded, err) in vectors {
+        assert_eq!(
+            Proof::decode(&String::from(encoded), &catalog),
+            Err(err),
+            "{}",
+            encoded
+        );
+    }
+    assert_eq!(
+        Proof::decode(&String::from("v2.0....."), &catalog).map(|p| p.predicates),
+        Ok(vec![])
+    );
+}
+
+#[test]
+fn parses_albedo_signed_message() {
+    let blob = base64::encode(format!("{}:{}:{}", "abcd", ISSUER, "v1.2.0.id"));
+    let (signature, pub_key, message) = proof_encoding::parse_albedo_signed_message(&blob).unwrap();
+
+    assert_eq!(signature, "abcd");
+    assert_eq!(pub_key, ISSUER);
+    assert_eq!(message, "v1.2.0.id");
+
+    let invalid = vec![
+        String::from("not base64!"),
+        base64::encode("a:b"),
+        base64::encode([0xff, 0xfe]),
+    ];
+    for invalid in invalid {
+        assert_eq!(
+            proof_encoding::parse_albedo_signed_message(&invalid),
+            Err(Error::ProofErr(ProofErr::ProofInvalidEncoding))
+        );
+    }
+}
+
+/// Signed with the secret seed made of the bytes 0x00..0x1f
+const SIGNER: &str = "GAB2CB576PHBBPQ5ODORRZ2LYCMWPZGWGCN2KDK7DXOIMZASKUY3QZ6Q";
+const SIGNED: &str = "v1.2.1632146400.test";
+/// Checked against albedos own verification library by `tests/albedo.rs`
+const ALBEDO_SIGNATURE: &str = "71dd8271a4dcfdc4648c43d1e339492cd9cbf12138146b7d97e944af6d5b933e99e7eb8e40a8e222d5006015139eceed2c7efa252abbfc1bd58edcfb1dca420e";
+/// Example of the SEP-53 specification
+const SEP53_SIGNER: &str = "GBXFXNDLV4LSWA4VB7YIL5GBD7BVNR22SGBTDKMO2SBZZHDXSKZYCP7L";
+const SEP53_MESSAGE: &str = "Hello, World!";
+const SEP53_SIGNATURE: &str = "7cee5d6d885752104c85eea421dfdcb95abf01f1271d11c4bec3fcbd7874dccd6e2e98b97b8eb23b643cac4073bb77de5d07b0710139180ae9f3cbba78f2ba04";
+
+fn signed(scheme: SignatureScheme, signature: &str) -> SignedMessage {
+    SignedMessage {
+        scheme,
+        network: String::from(proof_encoding::PUBLIC_NETWORK),
+        pubkey: String::from(SIGNER),
+        message: String::from(SIGNED),
+        signature: String::from(signature),
+        anchor: None,
+        cosigners: vec![],
+    }
+}
+
+#[test]
+fn encodes_legacy_blobs() {
+    let albedo = signed(SignatureScheme::Albedo, "abcd");
+    assert_eq!(
+        albedo.encode_legacy(),
+        base64::encode(format!("abcd:{}:{}", SIGNER, SIGNED))
+    );
+    let sep53 = signed(SignatureScheme::Sep53, "abcd");
+    assert_eq!(
+        sep53.encode_legacy(),
+        base64::encode(format!("sep53:abcd:{}:{}", SIGNER, SIGNED))
+    );
+
+    for signed in [albedo, sep53] {
+        assert_eq!(SignedMessage::parse(&signed.encode_legacy()), Ok(signed));
+    }
+
+    // sep53 proofs are not albedo proofs
+    assert!(proof_encoding::parse_albedo_signed_message(
+        &signed(SignatureScheme::Sep53, "abcd").encode_legacy()
+    )
+    .is_err());
+}
+
+#[test]
+fn encodes_envelopes() {
+    let signed = signed(SignatureScheme::Sep53, "abcd");
+    let encoded = signed.encode();
+    assert!(!encoded.contains(['+', '/', '=']));
+
+    let json: serde_json::Value =
+        serde_json::from_slice(&base64::decode_config(&encoded, base64::URL_SAFE_NO_PAD).unwrap())
+            .unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "version": 1,
+            "scheme": "sep53",
+            "network": "public",
+            "pubkey": SIGNER,
+            "signature": "abcd",
+            "proof": SIGNED,
+        })
+    );
+    assert_eq!(SignedMessage::parse(&encoded), Ok(signed));
+
+    // envelopes are not understood by the legacy decoder
+    assert!(proof_encoding::parse_albedo_signed_message(&encoded).is_err());
+}
+
+#[test]
+fn rejects_invalid_envelopes() {
+    let envelope =
+        |json: serde_json::Value| base64::encode_config(json.to_string(), base64::URL_SAFE_NO_PAD);
+    let vectors = vec![
+        (
+            envelope(serde_json::json!({ "version": 2, "proof": SIGNED })),
+            Error::ProofErr(ProofErr::ProofWrongVersion),
+        ),
+        (
+            envelope(serde_json::json!({ "version": 1, "proof": SIGNED })),
+            Error::ProofErr(ProofErr::ProofInvalidEncoding),
+        ),
+        (
+            envelope(serde_json::json!({
+                "version": 1,
+                "scheme": "unknown",
+                "network": "public",
+                "pubkey": SIGNER,
+                "signature": "abcd",
+                "proof": SIGNED,
+            })),
+            Error::ProofErr(ProofErr::ProofInvalidEncoding),
+        ),
+    ];
+
+    for (encoded, err) in vectors {
+        assert_eq!(SignedMessage::parse(&encoded), Err(err), "{}", encoded);
+    }
+}
+
+#[test]
+fn encodes_anchors_in_envelopes() {
+    let mut signed = signed(SignatureScheme::Albedo, "abcd");
+    signed.anchor = Some("ab".repeat(32));
+    let encoded = signed.encode();
+
+    let json: serde_json::Value =
+        serde_json::from_slice(&base64::decode_config(&encoded, base64::URL_SAFE_NO_PAD).unwrap())
+            .unwrap();
+    assert_eq!(json["anchor"], serde_json::json!("ab".repeat(32)));
+    assert_eq!(SignedMessage::parse(&encoded), Ok(signed));
+}
+
+#[test]
+fn encodes_cosigners_in_envelopes() {
+    let mut signed = signed(SignatureScheme::Albedo, "abcd");
+    signed.cosigners.push(Cosignature {
+        scheme: SignatureScheme::Sep53,
+        pubkey: String::from(ISSUER),
+        signature: String::from("ef01"),
+    });
+    let encoded = signed.encode();
+
+    let json: serde_json::Value =
+        serde_json::from_slice(&base64::decode_config(&encoded, base64::URL_SAFE_NO_PAD).unwrap())
+            .unwrap();
+    assert_eq!(
+        json["cosigners"],
+        serde_json::json!([{ "scheme": "sep53", "pubkey": ISSUER, "signature": "ef01" }])
+    );
+    assert_eq!(SignedMessage::parse(&encoded), Ok(signed.clone()));
+    assert_eq!(signed.accounts(), vec![SIGNER, ISSUER]);
+
+    // all accounts sign the message together with the sorted accounts
+    let accounts = [String::from(SIGNER), String::from(ISSUER)];
+    let reversed = [String::from(ISSUER), String::from(SIGNER)];
+    assert!(SIGNER < ISSUER);
+    assert_eq!(
+        signed.signed_text(),
+        proof_encoding::signed_text(SIGNED, &reversed)
+    );
+    assert_eq!(
+        proof_encoding::signed_text(SIGNED, &accounts),
+        format!("{}\naccounts:{}", SIGNED, accounts.join(","))
+    );
+    assert_eq!(proof_encoding::signed_text(SIGNED, &accounts[..1]), SIGNED);
+
+    // every account counts once
+    let mut duplicate = signed.clone();
+    duplicate.cosigners[0].pubkey = String::from(SIGNER);
+    assert_eq!(
+        SignedMessage::parse(&duplicate.encode()),
+        Err(Error::ProofErr(ProofErr::ProofInvalidEncoding))
+    );
+}
+
+#[test]
+fn keeps_colons_in_envelopes() {
+    let mut signed = signed(SignatureScheme::Albedo, "abcd");
+    signed.message = String::from("v1.2.0.a:b::c");
+    assert_eq!(SignedMessage::parse(&signed.encode()), Ok(signed));
+}
+
+#[test]
+fn keeps_colons_in_messages() {
+    let blob = base64::encode(format!("abcd:{}:v1.2.0.a:b:c", SIGNER));
+    let signed = SignedMessage::parse(&blob).unwrap();
+    assert_eq!(signed.scheme, SignatureScheme::Albedo);
+    assert_eq!(signed.message, "v1.2.0.a:b:c");
+}
+
+fn sep53_signed(scheme: SignatureScheme, signature: &str) -> SignedMessage {
+    SignedMessage {
+        pubkey: String::from(SEP53_SIGNER),
+        message: String::from(SEP53_MESSAGE),
+        ..signed(scheme, signature)
+    }
+}
+
+#[test]
+fn verifies_legacy_albedo_blobs() {
+    let blob = signed(SignatureScheme::Albedo, ALBEDO_SIGNATURE).encode_legacy();
+    assert_eq!(
+        proof_encoding::verify_albedo_signed_message(&blob),
+        Ok((true, String::from(SIGNED), String::from(SIGNER)))
+    );
+
+    let forged = signed(SignatureScheme::Albedo, SEP53_SIGNATURE).encode_legacy();
+    assert_eq!(
+        proof_encoding::verify_albedo_signed_message(&forged),
+        Ok((false, String::from(SIGNED), String::from(SIGNER)))
+    );
+    assert!(proof_encoding::verify_albedo_signed_message(&String::from("not base64!")).is_err());
+}
+
+#[test]
+fn verifies_both_schemes() {
+    assert_eq!(
+        signed(SignatureScheme::Albedo, ALBEDO_SIGNATURE).verify(),
+        Ok(true)
+    );
+    assert_eq!(
+        sep53_signed(SignatureScheme::Sep53, SEP53_SIGNATURE).verify(),
+        Ok(true)
+    );
+
+    // signatures are bound to their scheme
+    assert_eq!(
+        signed(SignatureScheme::Sep53, ALBEDO_SIGNATURE).verify(),
+        Ok(false)
+    );
+    assert_eq!(
+        sep53_signed(SignatureScheme::Albedo, SEP53_SIGNATURE).verify(),
+        Ok(false)
+    );
+
+    let mut tampered = sep53_signed(SignatureScheme::Sep53, SEP53_SIGNATURE);
+    tampered.message.push('x');
+    assert_eq!(tampered.verify(), Ok(false));
+
+    assert_eq!(
+        signed(SignatureScheme::Sep53, "not hex").verify(),
+        Ok(false)
+    );
+
+    let mut wrong_key = sep53_signed(SignatureScheme::Sep53, SEP53_SIGNATURE);
+    wrong_key.pubkey = String::from("GINVALID");
+    assert_eq!(
+        wrong_key.verify(),
+        Err(Error::StellarErr(StellarErr::InvalidPublicKey))
+    );
+}
+
+/// A catalog of random quests and a proof owning a random subset of them,
+/// in the shape `decode_v1` produces (catalog order, mono variants included)
+fn catalog_and_proof() -> impl Strategy<Value = (Vec<TOMLCurrency>, Proof)> {
+    let quests = prop::collection::btree_set((1usize..=20, 0usize..9), 0..60);
+    quests
+        .prop_flat_map(|quests| {
+            let quests = quests.into_iter().collect::<Vec<_>>();
+            let len = quests.len();
+            (
+                Just(quests),
+                prop::sample::subsequence((0..len).collect::<Vec<_>>(), 0..=len),
+                prop::option::of(any::<i64>()),
+                prop::option::of(".{1,24}"),
+            )
+        })
+        .prop_map(|(quests, owned, timestamp, unique_id)| {
+            let catalog = quests
+                .iter()
+                .flat_map(|(s, q)| {
+                    let mut currencies = vec![currency(&code(*s, *q), "color")];
+                    if q % 2 == 0 {
+                        currencies.push(currency(&code(*s, *q), "mono"));
+                    }
+                    currencies
+                })
+                .collect::<Vec<_>>();
+            let owned_codes = owned
+                .into_iter()
+                .map(|i| code(quests[i].0, quests[i].1))
+                .collect::<Vec<_>>();
+            let proof = Proof {
+                owned_badges: catalog
+                    .iter()
+                    .filter(|c| owned_codes.contains(&c.code))
+                    .cloned()
+                    .collect(),
+                timestamp,
+                unique_id,
+                predicates: vec![],
+            };
+            (catalog, proof)
+        })
+}
+
+proptest! {
+    #[test]
+    fn round_trips((catalog, proof) in catalog_and_proof()) {
+        let encoded = proof.encode_v1().unwrap();
+        prop_assert_eq!(Proof::decode_v1(&encoded, &catalog).unwrap(), proof);
+    }
+
+    #[test]
+    fn round_trips_predicates(
+        (catalog, mut proof) in catalog_and_proof(),
+        series in prop::collection::vec(1usize..100, 0..4),
+        count in prop::option::of(0usize..1000),
+    ) {
+        proof.predicates = series.into_iter().map(Predicate::CompleteSeries).chain(count.map(Predicate::AtLeast)).collect();
+        let encoded = proof.encode().unwrap();
+        prop_assert_eq!(Proof::decode(&encoded, &catalog).unwrap(), proof);
+    }
+
+    #[test]
+    fn decode_never_panics(encoded in ".*|v1\\.[0-9a-fA-F]{0,40}\\.-?[0-9]{0,20}\\..*") {
+        let _ = Proof::decode_v1(&encoded, &catalog(3));
+    }
+
+    #[test]
+    fn encode_never_panics(codes in prop::collection::vec("S{0,2}Q?[0-9]{0,5}|.{0,8}", 0..20)) {
+        let proof = Proof {
+            owned_badges: codes.iter().map(|c| currency(c, "color")).collect(),
+            ..Proof::default()
+        };
+        prop_assert!(proof.encode_v1().is_ok());
+    }
+
+    #[test]
+    fn parse_signed_message_never_panics(bytes in prop::collection::vec(any::<u8>(), 0..256)) {
+        let _ = proof_encoding::parse_albedo_signed_message(&base64::encode(&bytes));
+        let _ = SignedMessage::parse(&base64::encode_config(&bytes, base64::URL_SAFE_NO_PAD));
+        let _ = proof_encoding::parse_albedo_signed_message(&String::from_utf8_lossy(&bytes).into_owned());
+    }
+}
@@ -0,0 +1,106 @@
+//! Revocation entries and the manage_data transactions publishing them.
+#![cfg(not(target_arch = "wasm32"))]
+
+use sha2::{Digest, Sha256};
+use stellar_badge_wasm::stellar::stellar_data::Account;
+use stellar_badge_wasm::stellar::transaction;
+use stellar_badge_wasm::util::revocation;
+
+const SIGNER: &str = "GAB2CB576PHBBPQ5ODORRZ2LYCMWPZGWGCN2KDK7DXOIMZASKUY3QZ6Q";
+const SIGNED: &str = "v1.2.1632146400.test";
+
+#[test]
+fn names_data_entries_after_the_proof() {
+    // the id shown to users is the part of the hash stored on-chain
+    let id = revocation::proof_id(&String::from(SIGNED));
+    assert_eq!(id.len(), 48);
+    assert_eq!(id, hex::encode(Sha256::digest(SIGNED.as_bytes()))[..48]);
+
+    let name = revocation::data_name(&String::from(SIGNED));
+    assert_eq!(name.len(), transaction::MAX_DATA_LENGTH);
+    assert_eq!(name, format!("{}{}", revocation::DATA_PREFIX, id));
+}
+
+#[test]
+fn reads_revocations_from_horizon_accounts() {
+    let account = |data: serde_json::Value| -> Account {
+        serde_json::from_value(serde_json::json!({
+            "account_id": SIGNER,
+            "sequence": "41",
+            "balances": [],
+            "data": data,
+        }))
+        .unwrap()
+    };
+    let name = revocation::data_name(&String::from(SIGNED));
+
+    let revoked = account(serde_json::json!({ name: "MQ==" }));
+    assert!(revocation::is_revoked_by(&revoked, &String::from(SIGNED)));
+    assert!(!revocation::is_revoked_by(
+        &revoked,
+        &String::from("v1.2.1632146400.other")
+    ));
+    assert!(!revocation::is_revoked_by(
+        &account(serde_json::json!({})),
+        &String::from(SIGNED)
+    ));
+}
+
+#[test]
+fn builds_manage_data_transactions() {
+    let name = revocation::data_name(&String::from(SIGNED));
+    let envelope = revocation::revoke_transaction(
+        &String::from(SIGNER),
+        &String::from("41"),
+        &String::from(SIGNED),
+    )
+    .unwrap();
+    let raw = base64::decode(&envelope).unwrap();
+
+    // sequence, no memo, a single manage data operation
+    assert_eq!(raw[44..52], 42i64.to_be_bytes());
+    assert_eq!(raw[56..60], 0i32.to_be_bytes());
+    assert_eq!(raw[60..64], 1u32.to_be_bytes());
+    assert_eq!(raw[68..72], 10i32.to_be_bytes());
+    // the name, length prefixed
+    assert_eq!(raw[72..76], 64u32.to_be_bytes());
+    assert_eq!(&raw[76..140], name.as_bytes());
+    // the value, present and padded to 4 bytes
+    assert_eq!(raw[140..144], 1i32.to_be_bytes());
+    assert_eq!(raw[144..148], 1u32.to_be_bytes());
+    assert_eq!(raw[148..152], [b'1', 0, 0, 0]);
+    // ext, no signatures
+    assert_eq!(raw[152..], [0u8; 8]);
+}
+
+#[test]
+fn deletes_data_entries() {
+    let envelope = transaction::manage_data(
+        &String::from(SIGNER),
+        &String::from("41"),
+        &String::from("abcde"),
+        None,
+    )
+    .unwrap();
+    let raw = base64::decode(&envelope).unwrap();
+    assert_eq!(raw[72..76], 5u32.to_be_bytes());
+    assert_eq!(&raw[76..84], b"abcde\0\0\0");
+    assert_eq!(raw[84..88], 0i32.to_be_bytes());
+    assert_eq!(raw.len(), 88 + 8);
+}
+
+#[test]
+fn rejects_invalid_data_entries() {
+    let build = |name: &str, value: Option<&[u8]>| {
+        transaction::manage_data(
+            &String::from(SIGNER),
+            &String::from("41"),
+            &String::from(name),
+            value,
+        )
+    };
+    assert!(build("", None).is_err());
+    assert!(build(&"a".repeat(65), None).is_err());
+    assert!(build("a", Some(&[0u8; 65])).is_err());
+    assert!(build(&"a".repeat(64), Some(&[0u8; 64])).is_ok());
+}
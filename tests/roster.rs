@@ -0,0 +1,137 @@
+//! Importing rosters and ranking their members.
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{badge, BadgeFixture};
+use stellar_badge_wasm::util::badge_check::Provenance;
+use stellar_badge_wasm::util::roster::{self, Member};
+
+const FIRST: &str = "GAB2CB576PHBBPQ5ODORRZ2LYCMWPZGWGCN2KDK7DXOIMZASKUY3QZ6Q";
+const SECOND: &str = "GDL54NFRPNDJDKTXVOGBPL65IQFQAS6IPVOZVOMRVAUW2AZPVW4GO2TD";
+const THIRD: &str = "GDXSQPNVVG7326K5ZTWGBSHLGYCIHG3RATBMMTXQJSJL6CMSIBDQ5SPD";
+
+fn member(account: &str, name: &str) -> Member {
+    Member {
+        account: String::from(account),
+        name: String::from(name),
+    }
+}
+
+#[test]
+fn parses_spreadsheet_exports() {
+    let csv = format!(
+        "account,name\n{},Ada Lovelace\n\n# left the cohort\n\"Grace Hopper\";\"{}\"\n{}\t\n{},Ada again\n",
+        FIRST, SECOND, THIRD, FIRST
+    );
+    assert_eq!(
+        roster::parse(&csv).unwrap(),
+        vec![
+            member(FIRST, "Ada Lovelace"),
+            member(SECOND, "Grace Hopper"),
+            // accounts without name are shown by id
+            member(THIRD, THIRD),
+        ]
+    );
+}
+
+#[test]
+fn rejects_rows_without_account() {
+    let csv = format!("{},Ada\nGBROKEN,Grace\n", FIRST);
+    let err = roster::parse(&csv).unwrap_err();
+    assert!(err.to_string().contains("Line 2"), "{}", err);
+
+    assert!(roster::parse("account,name\n").is_err());
+    assert!(roster::parse("").is_err());
+}
+
+#[test]
+fn ranks_members_by_earned_badges() {
+    let earned = Some(Provenance::IssuerPayment);
+    let transferred = Some(Provenance::Transferred {
+        from: String::from(THIRD),
+    });
+    let members = vec![
+        member(FIRST, "Ada"),
+        member(SECOND, "Grace"),
+        member(THIRD, "Katherine"),
+    ];
+    let badges = vec![
+        vec![
+            badge("SSQ01", "color", None),
+            badge("SQ0101", "color", earned.clone())
+                .held(true)
+                .acquired(Some("2021-09-01T10:00:00Z")),
+            badge("SQ0102", "color", earned.clone())
+                .held(true)
+                .acquired(Some("2021-09-03T10:00:00Z")),
+            badge("SQ0201", "color", None),
+        ],
+        vec![
+            badge("SSQ01", "color", None),
+            badge("SQ0101", "color", earned.clone())
+                .held(true)
+                .acquired(Some("2021-08-01T10:00:00Z")),
+            badge("SQ0102", "color", earned.clone())
+                .held(true)
+                .acquired(Some("2021-09-02T10:00:00Z")),
+            badge("SQ0201", "color", None),
+        ],
+        vec![
+            badge("SSQ01", "color", None),
+            badge("SQ0101", "color", transferred.clone())
+                .held(true)
+                .acquired(Some("2021-07-01T10:00:00Z")),
+            badge("SQ0102", "color", None),
+            badge("SQ0201", "color", earned.clone())
+                .held(true)
+                .acquired(Some("2021-07-02T10:00:00Z")),
+        ],
+    ];
+
+    let leaderboard = roster::leaderboard(&members, &badges, String::from("now"));
+    assert_eq!(
+        leaderboard
+            .standings
+            .iter()
+            .map(|s| (s.member.name.as_str(), s.completed))
+            .collect::<Vec<_>>(),
+        // Grace got both badges first, transferred badges do not count
+        vec![("Grace", 2), ("Ada", 2), ("Katherine", 1)]
+    );
+    assert_eq!(
+        leaderboard.standings[0].series,
+        vec![(1, String::from("2021-09-02T10:00:00Z"))]
+    );
+    // series 2 has a single quest
+    assert_eq!(
+        leaderboard.standings[2].series,
+        vec![(2, String::from("2021-07-02T10:00:00Z"))]
+    );
+
+    let winners = roster::first_to_finish(&leaderboard);
+    assert_eq!(
+        winners
+            .iter()
+            .map(|(series, member, _)| (*series, member.name.as_str()))
+            .collect::<Vec<_>>(),
+        vec![(1, "Grace"), (2, "Katherine")]
+    );
+}
+
+#[test]
+fn caches_as_json() {
+    let cached = roster::CachedRoster {
+        members: vec![member(FIRST, "Ada")],
+        leaderboard: Some(roster::Leaderboard {
+            standings: vec![roster::standing(&member(FIRST, "Ada"), &[])],
+            updated_at: String::from("2021-09-20T12:00:00+00:00"),
+            failed: vec![(member(SECOND, "Grace"), String::from("Not found"))],
+        }),
+    };
+    let json = serde_json::to_string(&cached).unwrap();
+    assert_eq!(
+        serde_json::from_str::<roster::CachedRoster>(&json).unwrap(),
+        cached
+    );
+}
@@ -0,0 +1,14 @@
+#!/bin/sh
+# Runs the integration tests against the fake horizon.
+# Requires node and wasm-pack, BROWSER selects the headless browser (default: firefox).
+# Arguments go to wasm-pack test, `-- --features dev-signer` also runs tests/albedo.rs.
+set -e
+
+cd "$(dirname "$0")/.."
+
+PORT=${PORT:-8001}
+PORT=$PORT PAGE_SIZE=2 node tests/fake_horizon/server.js > /dev/null &
+SERVER=$!
+trap 'kill $SERVER' EXIT
+
+FAKE_HORIZON="http://127.0.0.1:$PORT/" wasm-pack test --headless --"${BROWSER:-firefox}" "$@"
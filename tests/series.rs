@@ -0,0 +1,104 @@
+//! Deriving quest series from the catalog and the progress of an account in them.
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{badge, currency, BadgeFixture};
+use stellar_badge_wasm::stellar::stellar_data::TOMLCurrency;
+use stellar_badge_wasm::util::badge_check::{Badge, Provenance};
+use stellar_badge_wasm::util::series::{self, Series};
+
+fn catalog() -> Vec<TOMLCurrency> {
+    vec![
+        currency("SQ0101", "color"),
+        currency("SQ0101", "mono"),
+        currency("SQ0102", "color"),
+        currency("SQ0201", "color"),
+        currency("SSQ01", "color"),
+        currency("SQ0103", "color"),
+        currency("SSQ03", "color"),
+        currency("GOLD", "color"),
+    ]
+}
+
+#[test]
+fn derives_series_from_the_catalog() {
+    let series = Series::from_catalog(&catalog());
+    assert_eq!(
+        series,
+        vec![
+            Series {
+                number: 1,
+                quests: vec![
+                    String::from("SQ0101"),
+                    String::from("SQ0102"),
+                    String::from("SQ0103")
+                ],
+                special: Some(String::from("SSQ01")),
+                ..Series::default()
+            },
+            Series {
+                number: 2,
+                quests: vec![String::from("SQ0201")],
+                ..Series::default()
+            },
+            // a special badge without quests
+            Series {
+                number: 3,
+                special: Some(String::from("SSQ03")),
+                ..Series::default()
+            },
+        ]
+    );
+    assert!(!series[2].is_complete());
+    assert_eq!(series[2].completion(), 0);
+}
+
+#[test]
+fn tracks_progress_and_completion() {
+    let catalog = catalog();
+    let earned = Some(Provenance::IssuerPayment);
+    let badges = vec![
+        badge("SQ0101", "color", None),
+        badge("SQ0101", "mono", earned.clone()).acquired(Some("2021-09-01T10:00:00Z")),
+        badge("SQ0102", "color", earned.clone()).acquired(Some("2021-09-03T10:00:00Z")),
+        badge(
+            "SQ0201",
+            "color",
+            Some(Provenance::Transferred {
+                from: String::from("GABC"),
+            }),
+        )
+        .acquired(Some("2021-09-02T10:00:00Z")),
+        badge("SSQ01", "color", None),
+        badge("SQ0103", "color", earned.clone()).acquired(Some("2021-09-02T10:00:00Z")),
+    ];
+
+    let progress = series::progress(&catalog, &badges, Badge::is_earned);
+    assert!(progress[0].is_complete());
+    assert_eq!(progress[0].completion(), 100);
+    assert_eq!(
+        progress[0].completed_at,
+        Some(String::from("2021-09-03T10:00:00Z"))
+    );
+    // transferred badges are not earned
+    assert!(!progress[1].is_complete());
+    assert_eq!(progress[1].completed_at, None);
+
+    let owned = series::progress(&catalog, &badges, |b| b.owned);
+    assert!(owned[1].is_complete());
+
+    let partial = series::progress(&catalog, &badges, |b| b.owned && b.token.code != "SQ0103");
+    assert_eq!(partial[0].completed_quests, 2);
+    assert_eq!(partial[0].completion(), 66);
+    assert_eq!(partial[0].completed_at, None);
+}
+
+#[test]
+fn groups_badges_by_series() {
+    let groups = ["SQ0101", "SSQ01", "SQ1203", "SSQ12", "GOLD1"]
+        .iter()
+        .map(|code| series::group_key(&String::from(*code)))
+        .collect::<Vec<String>>();
+    assert_eq!(groups, vec!["SQ01", "SQ01", "SQ12", "SQ12", "GOLD"]);
+}
@@ -0,0 +1,88 @@
+//! Ordering acquired badges in time and charting them.
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use chrono::Duration;
+use common::{badge, earned, BadgeFixture};
+use stellar_badge_wasm::util::badge_check::Badge;
+use stellar_badge_wasm::util::timeline;
+
+fn badges() -> Vec<Badge> {
+    vec![
+        earned("SQ0101", "color").acquired(Some("2021-09-03T10:00:00Z")),
+        earned("SQ0101", "mono").acquired(Some("2021-09-01T10:00:00Z")),
+        earned("SQ0102", "color").acquired(Some("2021-09-02T16:30:00Z")),
+        badge("SQ0103", "color", None),
+        earned("SQ0201", "color").acquired(Some("2021-09-05T10:00:00Z")),
+        earned("SSQ01", "color").acquired(Some("2021-09-04T10:00:00Z")),
+        earned("SQ0104", "color").acquired(Some("no date")),
+    ]
+}
+
+#[test]
+fn orders_badges_by_acquisition() {
+    let entries = timeline::entries(&badges());
+    assert_eq!(
+        entries
+            .iter()
+            .map(|e| (
+                e.code.as_str(),
+                e.date.format("%m-%d").to_string(),
+                e.series
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            // the mono variant was acquired first
+            ("SQ0101", String::from("09-01"), Some(1)),
+            ("SQ0102", String::from("09-02"), Some(1)),
+            ("SSQ01", String::from("09-04"), Some(1)),
+            ("SQ0201", String::from("09-05"), Some(2)),
+        ]
+    );
+
+    let grouped = timeline::by_series(&entries);
+    assert_eq!(
+        grouped
+            .iter()
+            .map(|(series, entries)| (*series, entries.len()))
+            .collect::<Vec<_>>(),
+        vec![(Some(1), 3), (Some(2), 1)]
+    );
+}
+
+#[test]
+fn averages_the_time_between_badges() {
+    let entries = timeline::entries(&badges());
+    assert_eq!(
+        timeline::average_interval(&entries),
+        Some(Duration::hours(32))
+    );
+    assert_eq!(timeline::average_interval(&entries[..1]), None);
+
+    assert_eq!(
+        timeline::describe_duration(Duration::hours(32)),
+        "1 day 8 hours"
+    );
+    assert_eq!(timeline::describe_duration(Duration::days(3)), "3 days");
+    assert_eq!(
+        timeline::describe_duration(Duration::minutes(61)),
+        "1 hour 1 minute"
+    );
+    assert_eq!(
+        timeline::describe_duration(Duration::seconds(30)),
+        "0 minutes"
+    );
+}
+
+#[test]
+fn charts_the_cumulative_count() {
+    let entries = timeline::entries(&badges());
+    let svg = timeline::chart_svg(&entries, 400, 200);
+    assert!(svg.starts_with("<svg") && svg.ends_with("</svg>"));
+    assert_eq!(svg.matches("<circle").count(), entries.len());
+    assert!(svg.contains(">2021-09-01<") && svg.contains(">2021-09-05<"));
+
+    let empty = timeline::chart_svg(&[], 400, 200);
+    assert!(!empty.contains("<circle"));
+}
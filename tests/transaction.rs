@@ -0,0 +1,67 @@
+//! Golden transaction envelopes, decoded with the stellar sdk by `tests/xdr/decode.js`.
+#![cfg(not(target_arch = "wasm32"))]
+
+use serde::Deserialize;
+use stellar_badge_wasm::stellar::transaction;
+
+#[derive(Deserialize)]
+struct ClaimFixture {
+    name: String,
+    account: String,
+    sequence: String,
+    balance_id: String,
+    asset_code: String,
+    asset_issuer: String,
+    add_trustline: bool,
+    envelope: String,
+}
+
+fn claim_fixtures() -> Vec<ClaimFixture> {
+    serde_json::from_str(include_str!("fixtures/xdr/claim_claimable_balance.json")).unwrap()
+}
+
+#[test]
+fn builds_claim_transactions() {
+    for fixture in claim_fixtures() {
+        let envelope = transaction::claim_claimable_balance(
+            &fixture.account,
+            &fixture.sequence,
+            &fixture.balance_id,
+            &fixture.asset_code,
+            &fixture.asset_issuer,
+            fixture.add_trustline,
+        )
+        .unwrap();
+        assert_eq!(envelope, fixture.envelope, "{}", fixture.name);
+    }
+}
+
+#[test]
+fn rejects_invalid_asset_codes() {
+    let fixture = &claim_fixtures()[0];
+    for code in ["", "SQ0101SQ0101X", "SQ-1", "SQ 01"] {
+        assert!(
+            transaction::claim_claimable_balance(
+                &fixture.account,
+                &fixture.sequence,
+                &fixture.balance_id,
+                code,
+                &fixture.asset_issuer,
+                true,
+            )
+            .is_err(),
+            "{}",
+            code
+        );
+    }
+    // the code is only encoded with a new trustline
+    assert!(transaction::claim_claimable_balance(
+        &fixture.account,
+        &fixture.sequence,
+        &fixture.balance_id,
+        "",
+        &fixture.asset_issuer,
+        false,
+    )
+    .is_ok());
+}
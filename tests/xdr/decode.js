@@ -0,0 +1,44 @@
+// Decodes the golden envelopes of tests/fixtures/xdr with the stellar sdk, the same envelopes
+// tests/transaction.rs expects the rust transaction builder to produce.
+//
+//   npm install --prefix tests/xdr && node tests/xdr/decode.js
+
+const assert = require("assert");
+const path = require("path");
+const { Networks, TransactionBuilder } = require("@stellar/stellar-base");
+
+const FIXTURES = path.join(__dirname, "..", "fixtures", "xdr");
+const MAX_LIMIT = "922337203685.4775807";
+
+function checkClaim(fixture) {
+    const tx = TransactionBuilder.fromXDR(fixture.envelope, Networks.PUBLIC);
+    const operations = fixture.add_trustline ? 2 : 1;
+
+    assert.strictEqual(tx.source, fixture.account);
+    assert.strictEqual(tx.sequence, (BigInt(fixture.sequence) + 1n).toString());
+    assert.strictEqual(tx.fee, String(100 * operations));
+    assert.strictEqual(tx.memo.type, "none");
+    assert.strictEqual(tx.signatures.length, 0);
+    assert.strictEqual(tx.operations.length, operations);
+
+    if (fixture.add_trustline) {
+        const trust = tx.operations[0];
+        assert.strictEqual(trust.type, "changeTrust");
+        assert.strictEqual(trust.line.getCode(), fixture.asset_code);
+        assert.strictEqual(trust.line.getIssuer(), fixture.asset_issuer);
+        assert.strictEqual(
+            trust.line.getAssetType(),
+            fixture.asset_code.length <= 4 ? "credit_alphanum4" : "credit_alphanum12"
+        );
+        assert.strictEqual(trust.limit, MAX_LIMIT);
+    }
+
+    const claim = tx.operations[operations - 1];
+    assert.strictEqual(claim.type, "claimClaimableBalance");
+    assert.strictEqual(claim.balanceId, fixture.balance_id);
+}
+
+for (const fixture of require(path.join(FIXTURES, "claim_claimable_balance.json"))) {
+    checkClaim(fixture);
+    console.log(`ok: ${fixture.name}`);
+}